@@ -0,0 +1,165 @@
+use puzzle::PuzzleError;
+use std::collections::BTreeMap;
+
+/// Accepted answers, keyed by day, input file name and part, as stored
+/// in `answers.toml`:
+///
+/// ```toml
+/// [day1."puzzle-input.txt"]
+/// part1 = 1666427
+/// part2 = "24316233"
+/// ```
+#[derive(Debug, Default)]
+pub struct Registry {
+    answers: BTreeMap<(u32, String, u8), String>,
+}
+
+type RawRegistry = BTreeMap<String, BTreeMap<String, BTreeMap<String, toml::Value>>>;
+
+impl Registry {
+    pub fn load(file: &str) -> Result<Registry, PuzzleError> {
+        let raw_content = puzzle::read_input(file)?;
+        Registry::parse(&raw_content).map_err(|e| e.in_file(file))
+    }
+
+    pub fn parse(raw_content: &str) -> Result<Registry, PuzzleError> {
+        let raw: RawRegistry = toml::from_str(raw_content).map_err(|e| {
+            let description = e.message().to_string();
+            match e.span() {
+                Some(span) => {
+                    let location = puzzle::location_of(raw_content, span.start);
+                    PuzzleError::at(location.line, location.column, description)
+                }
+                None => PuzzleError::invalid(description),
+            }
+        })?;
+
+        let mut answers = BTreeMap::new();
+        for (day_key, inputs) in raw {
+            let day = day_key
+                .strip_prefix("day")
+                .and_then(|n| n.parse::<u32>().ok())
+                .ok_or_else(|| {
+                    PuzzleError::invalid(format!("invalid day '{day_key}', expected dayN"))
+                })?;
+
+            for (input, parts) in inputs {
+                for (part_key, value) in parts {
+                    let part = match part_key.as_str() {
+                        "part1" => 1,
+                        "part2" => 2,
+                        _ => {
+                            return Err(PuzzleError::invalid(format!(
+                                "{day_key}.{input}: invalid part '{part_key}', \
+                                 expected part1 or part2"
+                            )))
+                        }
+                    };
+
+                    let answer = match value {
+                        toml::Value::String(s) => s,
+                        toml::Value::Integer(i) => i.to_string(),
+                        other => {
+                            return Err(PuzzleError::invalid(format!(
+                                "{day_key}.{input}.{part_key}: expected a string or an \
+                                 integer, found {}",
+                                other.type_str()
+                            )))
+                        }
+                    };
+
+                    answers.insert((day, input.clone(), part), answer);
+                }
+            }
+        }
+
+        Ok(Registry { answers })
+    }
+
+    pub fn get(&self, day: u32, input: &str, part: u8) -> Option<&str> {
+        self.answers
+            .get(&(day, input.to_string(), part))
+            .map(|a| a.as_str())
+    }
+}
+
+/// Outcome of comparing an answer with the registry.
+#[derive(Debug, PartialEq)]
+pub enum Verdict {
+    Pass,
+    Fail { expected: String },
+    Unknown,
+}
+
+impl Verdict {
+    pub fn check(expected: Option<&str>, actual: Option<&str>) -> Verdict {
+        match (expected, actual) {
+            (None, _) => Verdict::Unknown,
+            (Some(expected), Some(actual)) if expected == actual => Verdict::Pass,
+            (Some(expected), _) => Verdict::Fail {
+                expected: expected.to_string(),
+            },
+        }
+    }
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_registry() {
+        let data = r#"
+[day1."puzzle-input.txt"]
+part1 = 1666427
+part2 = "24316233"
+
+[day15."day15.txt"]
+part2 = 1458740
+"#;
+
+        let registry = Registry::parse(data).unwrap();
+        assert_eq!(registry.get(1, "puzzle-input.txt", 1), Some("1666427"));
+        assert_eq!(registry.get(1, "puzzle-input.txt", 2), Some("24316233"));
+        assert_eq!(registry.get(15, "day15.txt", 1), None);
+        assert_eq!(registry.get(15, "day15.txt", 2), Some("1458740"));
+        assert_eq!(registry.get(2, "puzzle-input.txt", 1), None);
+    }
+
+    #[test]
+    fn parse_registry_errors() {
+        let error = Registry::parse("[day1.\"a.txt\"]\npart3 = 1\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "day1.a.txt: invalid part 'part3', expected part1 or part2"
+        );
+
+        let error = Registry::parse("[one.\"a.txt\"]\npart1 = 1\n").unwrap_err();
+        assert_eq!(error.to_string(), "invalid day 'one', expected dayN");
+
+        let error = Registry::parse("[day1.\"a.txt\"]\npart1 = \n").unwrap_err();
+        assert!(error.to_string().starts_with("line 2, column 9: "));
+    }
+
+    #[test]
+    fn verdicts() {
+        assert_eq!(Verdict::check(Some("42"), Some("42")), Verdict::Pass);
+        assert_eq!(
+            Verdict::check(Some("42"), Some("41")),
+            Verdict::Fail {
+                expected: String::from("42")
+            }
+        );
+        assert_eq!(
+            Verdict::check(Some("42"), None),
+            Verdict::Fail {
+                expected: String::from("42")
+            }
+        );
+        assert_eq!(Verdict::check(None, Some("42")), Verdict::Unknown);
+    }
+}
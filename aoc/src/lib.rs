@@ -0,0 +1,640 @@
+mod answers;
+
+use answers::{Registry, Verdict};
+use fetch::{FetchError, InputProvider};
+use puzzle::output::{Format, Output, Record};
+use puzzle::PuzzleError;
+use std::error::Error;
+use std::fmt::{self, Display};
+use std::panic;
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+const USAGE: &str = "Usage: aoc <run|verify> <day|all> [--input <path>]... [--answers <path>] \
+                     [--format <json|csv|text>]";
+
+// accepted answers, relative to the workspace root
+const ANSWERS_FILE: &str = "answers.toml";
+
+#[derive(Debug, PartialEq)]
+enum Command {
+    Run,
+    Verify,
+}
+
+#[derive(Debug, PartialEq)]
+enum Selection {
+    Day(u32),
+    All,
+}
+
+#[derive(Debug)]
+pub struct Config {
+    command: Command,
+    selection: Selection,
+    inputs: Vec<String>,
+    answers_file: Option<String>,
+    format: Format,
+}
+
+impl Config {
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
+        let mut args = args.to_vec();
+        let format = Format::take(&mut args)?;
+
+        if args.len() < 3 {
+            return Err(PuzzleError::usage(USAGE));
+        }
+
+        let command = match args[1].as_str() {
+            "run" => Command::Run,
+            "verify" => Command::Verify,
+            _ => {
+                return Err(PuzzleError::usage(
+                    "Unknown command: must be `run` or `verify`",
+                ))
+            }
+        };
+
+        let selection = match args[2].as_str() {
+            "all" => Selection::All,
+            day => match day.parse::<u32>() {
+                Ok(number) if find_day(number).is_some() => Selection::Day(number),
+                _ => {
+                    return Err(PuzzleError::usage(
+                        "Invalid day: must be `all` or one of the implemented days",
+                    ))
+                }
+            },
+        };
+
+        let mut inputs = Vec::new();
+        let mut answers_file = None;
+        let mut remaining = args[3..].iter();
+        while let Some(arg) = remaining.next() {
+            match arg.as_str() {
+                "--input" => match remaining.next() {
+                    Some(path) => inputs.push(path.clone()),
+                    None => return Err(PuzzleError::usage("Missing path after --input")),
+                },
+                "--answers" if command == Command::Verify => match remaining.next() {
+                    Some(path) => answers_file = Some(path.clone()),
+                    None => return Err(PuzzleError::usage("Missing path after --answers")),
+                },
+                _ => return Err(PuzzleError::usage(USAGE)),
+            }
+        }
+
+        if selection == Selection::All && !inputs.is_empty() {
+            return Err(PuzzleError::usage(
+                "--input can only be used when running a single day",
+            ));
+        }
+
+        Ok(Config {
+            command,
+            selection,
+            inputs,
+            answers_file,
+            format,
+        })
+    }
+}
+
+/// Answers of a single day, rendered for printing. Days that only
+/// implement one of the two parts leave the other one empty.
+#[derive(Debug)]
+struct Answers {
+    part1: Option<String>,
+    part2: Option<String>,
+}
+
+impl Answers {
+    fn both(part1: impl Display, part2: impl Display) -> Answers {
+        Answers {
+            part1: Some(part1.to_string()),
+            part2: Some(part2.to_string()),
+        }
+    }
+}
+
+type Solver = fn(&[String]) -> Result<Answers, PuzzleError>;
+
+struct Day {
+    number: u32,
+    // inputs bundled with the day, relative to the workspace root
+    inputs: &'static [&'static str],
+    // trailing arguments passed after the inputs
+    extra_args: &'static [&'static str],
+    solve: Solver,
+}
+
+const DAYS: &[Day] = &[
+    Day {
+        number: 1,
+        inputs: &["day1/content/puzzle-input.txt"],
+        extra_args: &[],
+        solve: solve_day1,
+    },
+    Day {
+        number: 2,
+        inputs: &["day2/content/puzzle-input.txt"],
+        extra_args: &[],
+        solve: solve_day2,
+    },
+    Day {
+        number: 4,
+        inputs: &["day4/content/puzzle-input.txt"],
+        extra_args: &[],
+        solve: solve_day4,
+    },
+    Day {
+        number: 5,
+        inputs: &[
+            "day5/content/rules-input.txt",
+            "day5/content/updates-input.txt",
+        ],
+        extra_args: &[],
+        solve: solve_day5,
+    },
+    Day {
+        number: 6,
+        inputs: &["day6/content/puzzle-input.txt"],
+        extra_args: &["^"],
+        solve: solve_day6,
+    },
+    Day {
+        number: 8,
+        inputs: &["day8/content/puzzle-input.txt"],
+        extra_args: &[],
+        solve: solve_day8,
+    },
+    Day {
+        number: 10,
+        inputs: &["day10/content/puzzle-input.txt"],
+        extra_args: &[],
+        solve: solve_day10,
+    },
+    Day {
+        number: 15,
+        inputs: &[
+            "day15/content/puzzle_input_map.txt",
+            "day15/content/puzzle_input_moves.txt",
+        ],
+        extra_args: &[],
+        solve: solve_day15,
+    },
+];
+
+fn find_day(number: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|d| d.number == number)
+}
+
+fn solve_day1(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day1::Config::build(args)?;
+    let (total_distance, similarity_score) = day1::run(config)?;
+    Ok(Answers::both(total_distance, similarity_score))
+}
+
+fn solve_day2(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day2::Config::build(args)?;
+    let (num_safe, num_loosely_safe) = day2::run(config)?;
+    Ok(Answers::both(num_safe, num_loosely_safe))
+}
+
+fn solve_day4(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day4::Config::build(args)?;
+    let (total, total_cross_mas) = day4::run(config)?;
+    Ok(Answers::both(total, total_cross_mas))
+}
+
+fn solve_day5(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day5::Config::build(args)?;
+    let (middle_page_sum, invalid_middle_page_sum) = day5::run(config)?;
+    Ok(Answers::both(middle_page_sum, invalid_middle_page_sum))
+}
+
+fn solve_day6(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day6::Config::build(args)?;
+    let (total, total_obstacles) = day6::run(config)?;
+    Ok(Answers::both(total, total_obstacles))
+}
+
+fn solve_day8(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day8::Config::build(args)?;
+    let (unique_antinodes, unique_harmonic_antinodes) = day8::run(config)?;
+    Ok(Answers::both(unique_antinodes, unique_harmonic_antinodes))
+}
+
+fn solve_day10(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day10::Config::build(args)?;
+    let (score, ratings) = day10::run(config)?;
+    Ok(Answers::both(score, ratings))
+}
+
+fn solve_day15(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day15::Config::build(args)?;
+    let (narrow_sum, sum) = day15::run(config)?;
+    Ok(Answers::both(narrow_sum, sum))
+}
+
+fn bundled_inputs(day: &Day) -> Vec<String> {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .parent()
+        .expect("runner crate lives inside the workspace");
+    day.inputs
+        .iter()
+        .map(|input| root.join(input).to_string_lossy().into_owned())
+        .collect()
+}
+
+/// Inputs given on the command line win. Otherwise the input provider is
+/// asked for the day, unless it has neither a cached copy nor a session
+/// to download one: then the inputs bundled with the day are used.
+fn resolve_inputs(
+    day: &Day,
+    inputs: &[String],
+    provider: &InputProvider,
+) -> Result<Vec<String>, FetchError> {
+    if !inputs.is_empty() {
+        return Ok(inputs.to_vec());
+    }
+
+    if provider.is_cached(day.number) || provider.has_session() {
+        let path = provider.input(day.number)?;
+        return Ok(vec![path.to_string_lossy().into_owned()]);
+    }
+
+    Ok(bundled_inputs(day))
+}
+
+fn build_day_args(day: &Day, inputs: &[String]) -> Vec<String> {
+    let mut args = vec![format!("day{}", day.number)];
+    args.extend(inputs.iter().cloned());
+    args.extend(day.extra_args.iter().map(|arg| arg.to_string()));
+    args
+}
+
+/// Answers of a day along with the input they were computed from.
+struct Solved {
+    answers: Answers,
+    // path of the first input
+    input: String,
+    // file name of the first input, used to look answers up
+    input_name: String,
+    elapsed: Duration,
+}
+
+/// Why a day could not be solved.
+#[derive(Debug)]
+enum Failure {
+    Fetch(FetchError),
+    Puzzle(PuzzleError),
+    Panic,
+}
+
+impl Failure {
+    fn record(&self, day: u32, input: Option<&str>) -> Record {
+        match self {
+            Failure::Puzzle(e) => Record::puzzle_error(Some(day), input, e),
+            other => Record::error(Some(day), input, other),
+        }
+    }
+}
+
+impl fmt::Display for Failure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Failure::Fetch(e) => write!(f, "{e}"),
+            Failure::Puzzle(e) => write!(f, "{e}"),
+            Failure::Panic => write!(f, "panicked"),
+        }
+    }
+}
+
+/// A failure along with the input the day was run on.
+#[derive(Debug)]
+struct Failed {
+    failure: Failure,
+    // path of the first input, unknown when the inputs could not be resolved
+    input: Option<String>,
+}
+
+fn run_day(day: &Day, inputs: &[String], provider: &InputProvider) -> Result<Solved, Failed> {
+    let inputs = resolve_inputs(day, inputs, provider).map_err(|e| Failed {
+        failure: Failure::Fetch(e),
+        input: None,
+    })?;
+    let args = build_day_args(day, &inputs);
+    let input = inputs.first().cloned().unwrap_or_default();
+    let input_name = Path::new(&input)
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    // a panicking day must not take the remaining days down with it
+    let start = Instant::now();
+    let result = panic::catch_unwind(|| (day.solve)(&args));
+    let elapsed = start.elapsed();
+
+    match result {
+        Ok(Ok(answers)) => Ok(Solved {
+            answers,
+            input,
+            input_name,
+            elapsed,
+        }),
+        Ok(Err(e)) => Err(Failed {
+            failure: Failure::Puzzle(e),
+            input: Some(input),
+        }),
+        Err(_) => Err(Failed {
+            failure: Failure::Panic,
+            input: Some(input),
+        }),
+    }
+}
+
+fn answer_record(day: &Day, solved: &Solved, part: u8, verdict: Option<&str>) -> Record {
+    let answer = match part {
+        1 => &solved.answers.part1,
+        _ => &solved.answers.part2,
+    };
+
+    Record::Answer {
+        day: day.number,
+        part,
+        answer: answer.clone(),
+        verdict: verdict.map(str::to_string),
+        input: solved.input.clone(),
+        elapsed: solved.elapsed,
+    }
+}
+
+fn print_answers(day: &Day, solved: &Solved, output: &Output) {
+    if !output.is_text() {
+        output.emit(&answer_record(day, solved, 1, None));
+        output.emit(&answer_record(day, solved, 2, None));
+        return;
+    }
+
+    let answers = &solved.answers;
+    let na = String::from("n/a");
+    println!(
+        "Day {:>2} part 1: {}",
+        day.number,
+        answers.part1.as_ref().unwrap_or(&na)
+    );
+    println!(
+        "Day {:>2} part 2: {}",
+        day.number,
+        answers.part2.as_ref().unwrap_or(&na)
+    );
+}
+
+/// Tally of a `verify` run.
+#[derive(Debug, Default)]
+struct Verification {
+    passed: usize,
+    failed: usize,
+    unknown: usize,
+}
+
+impl Verification {
+    fn verify_day(&mut self, day: &Day, solved: &Solved, registry: &Registry, output: &Output) {
+        let parts = [(1, &solved.answers.part1), (2, &solved.answers.part2)];
+        for (part, actual) in parts {
+            let expected = registry.get(day.number, &solved.input_name, part);
+            if expected.is_none() && actual.is_none() {
+                continue;
+            }
+
+            let verdict = Verdict::check(expected, actual.as_deref());
+            match verdict {
+                Verdict::Pass => self.passed += 1,
+                Verdict::Fail { .. } => self.failed += 1,
+                Verdict::Unknown => self.unknown += 1,
+            }
+
+            if !output.is_text() {
+                let verdict = match verdict {
+                    Verdict::Pass => "pass",
+                    Verdict::Fail { .. } => "fail",
+                    Verdict::Unknown => "unknown",
+                };
+                output.emit(&answer_record(day, solved, part, Some(verdict)));
+                continue;
+            }
+
+            let actual_text = actual.as_deref().unwrap_or("n/a");
+            let label = format!("Day {:>2} part {part}:", day.number);
+            match verdict {
+                Verdict::Pass => println!("{label} pass     {actual_text}"),
+                Verdict::Fail { expected } => {
+                    println!("{label} FAIL     {actual_text} (expected {expected})")
+                }
+                Verdict::Unknown => println!("{label} unknown  {actual_text}"),
+            }
+        }
+
+        if !output.is_text() {
+            return;
+        }
+
+        println!(
+            "Day {:>2} on {} took {:.2?}",
+            day.number, solved.input_name, solved.elapsed
+        );
+    }
+}
+
+fn answers_file(config: &Config) -> String {
+    config.answers_file.clone().unwrap_or_else(|| {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR"))
+            .parent()
+            .expect("runner crate lives inside the workspace");
+        root.join(ANSWERS_FILE).to_string_lossy().into_owned()
+    })
+}
+
+pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
+    let days: Vec<&Day> = match config.selection {
+        Selection::All => DAYS.iter().collect(),
+        Selection::Day(number) => vec![find_day(number).ok_or("Unknown day")?],
+    };
+
+    let registry = match config.command {
+        Command::Run => Registry::default(),
+        Command::Verify => Registry::load(&answers_file(&config))?,
+    };
+    let provider = InputProvider::from_env()?;
+    let output = Output::new(config.format);
+
+    let mut failures = 0;
+    let mut verification = Verification::default();
+    let start = Instant::now();
+    for day in days {
+        match run_day(day, &config.inputs, &provider) {
+            Ok(solved) => match config.command {
+                Command::Run => print_answers(day, &solved, &output),
+                Command::Verify => verification.verify_day(day, &solved, &registry, &output),
+            },
+            Err(Failed { failure, input }) => {
+                if output.is_text() {
+                    eprintln!("Day {:>2} failed: {failure}", day.number);
+                } else {
+                    output.emit(&failure.record(day.number, input.as_deref()));
+                }
+                failures += 1;
+            }
+        }
+    }
+
+    if config.command == Command::Verify && output.is_text() {
+        println!(
+            "{} passed, {} failed, {} unknown in {:.2?}",
+            verification.passed,
+            verification.failed,
+            verification.unknown,
+            start.elapsed()
+        );
+    }
+
+    if failures > 0 {
+        return Err(format!("{failures} day(s) failed").into());
+    }
+
+    if verification.failed > 0 {
+        return Err(format!("{} answer(s) do not match", verification.failed).into());
+    }
+
+    Ok(())
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn build_single_day_with_inputs() {
+        let config = Config::build(&args(&["aoc", "run", "6", "--input", "map.txt"])).unwrap();
+        assert_eq!(config.selection, Selection::Day(6));
+        assert_eq!(config.inputs, vec!["map.txt"]);
+    }
+
+    #[test]
+    fn build_verify_with_answers() {
+        let config =
+            Config::build(&args(&["aoc", "verify", "all", "--answers", "a.toml"])).unwrap();
+        assert_eq!(config.command, Command::Verify);
+        assert_eq!(config.selection, Selection::All);
+        assert_eq!(config.answers_file, Some(String::from("a.toml")));
+        assert_eq!(config.format, Format::Text);
+
+        let config = Config::build(&args(&["aoc", "--format", "json", "run", "2"])).unwrap();
+        assert_eq!(config.command, Command::Run);
+        assert_eq!(config.format, Format::Json);
+    }
+
+    #[test]
+    fn bundled_answers_cover_every_day() {
+        let registry = Registry::load(&answers_file(
+            &Config::build(&args(&["aoc", "verify", "all"])).unwrap(),
+        ))
+        .unwrap();
+        // days scaffolded without a bundled input yet have nothing to verify
+        for day in DAYS
+            .iter()
+            .filter(|day| bundled_inputs(day).iter().all(|i| Path::new(i).is_file()))
+        {
+            let input_name = Path::new(day.inputs[0])
+                .file_name()
+                .unwrap()
+                .to_string_lossy();
+            assert!(
+                registry.get(day.number, &input_name, 2).is_some(),
+                "day {}",
+                day.number
+            );
+        }
+    }
+
+    #[test]
+    fn build_rejects_invalid_selections() {
+        assert!(Config::build(&args(&["aoc", "run"])).is_err());
+        assert!(Config::build(&args(&["aoc", "run", "0"])).is_err());
+        assert!(Config::build(&args(&["aoc", "run", "all", "--input", "x"])).is_err());
+        assert!(Config::build(&args(&["aoc", "walk", "1"])).is_err());
+        assert!(Config::build(&args(&["aoc", "run", "1", "--answers", "a.toml"])).is_err());
+    }
+
+    fn offline_provider(cache_dir: &Path) -> InputProvider {
+        InputProvider::new(cache_dir, "http://127.0.0.1:1", None)
+    }
+
+    #[test]
+    fn default_day_args_point_to_content() {
+        let cache = tempfile::tempdir().unwrap();
+        let day = find_day(6).unwrap();
+        let inputs = resolve_inputs(day, &[], &offline_provider(cache.path())).unwrap();
+        let day_args = build_day_args(day, &inputs);
+        assert_eq!(day_args.len(), 3);
+        assert!(day_args[1].ends_with("day6/content/puzzle-input.txt"));
+        assert_eq!(day_args[2], "^");
+    }
+
+    #[test]
+    fn cached_input_is_preferred_to_content() {
+        let cache = tempfile::tempdir().unwrap();
+        let provider = offline_provider(cache.path());
+        std::fs::write(provider.cached_path(2), "7 6 4 2 1\n1 2 7 8 9\n").unwrap();
+
+        let day = find_day(2).unwrap();
+        let inputs = resolve_inputs(day, &[], &provider).unwrap();
+        assert_eq!(inputs, vec![provider.cached_path(2).to_string_lossy()]);
+
+        let explicit = vec![String::from("mine.txt")];
+        assert_eq!(resolve_inputs(day, &explicit, &provider).unwrap(), explicit);
+
+        let solved = run_day(day, &[], &provider).unwrap();
+        assert_eq!(solved.answers.part1, Some(String::from("1")));
+        assert_eq!(solved.input_name, "day2.txt");
+    }
+
+    #[test]
+    fn run_day1_with_explicit_input() {
+        let input = format!(
+            "{}/../day1/content/puzzle-input.txt",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let cache = tempfile::tempdir().unwrap();
+        let provider = offline_provider(cache.path());
+        let solved = run_day(find_day(1).unwrap(), &[input], &provider).unwrap();
+        assert!(solved.answers.part1.is_some());
+        assert!(solved.answers.part2.is_some());
+        assert_eq!(solved.input_name, "puzzle-input.txt");
+    }
+
+    #[test]
+    fn run_day_reports_missing_input() {
+        let cache = tempfile::tempdir().unwrap();
+        let provider = offline_provider(cache.path());
+        let result = run_day(
+            find_day(2).unwrap(),
+            &[String::from("does-not-exist.txt")],
+            &provider,
+        );
+        let Err(failed) = result else {
+            panic!("a missing input fails");
+        };
+        assert_eq!(failed.input.as_deref(), Some("does-not-exist.txt"));
+    }
+}
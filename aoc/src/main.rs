@@ -0,0 +1,14 @@
+use std::{env, process};
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    let config = aoc::Config::build(&args).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    if let Err(e) = aoc::run(config) {
+        eprintln!("Application error: {e}");
+        process::exit(1);
+    }
+}
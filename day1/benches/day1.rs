@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day1::Day1;
+use puzzle::bench;
+
+fn day1(c: &mut Criterion) {
+    let raw_input = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/puzzle-input.txt"
+    ));
+    bench::phases::<Day1>(c, "day1", &raw_input);
+}
+
+criterion_group!(benches, day1);
+criterion_main!(benches);
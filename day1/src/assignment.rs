@@ -0,0 +1,303 @@
+use puzzle::PuzzleError;
+use std::fmt;
+
+/// A location on a map, as (x, y).
+pub type Point = (i32, i32);
+
+/// How far apart two locations are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Manhattan,
+    Euclidean,
+}
+
+impl Metric {
+    pub(crate) fn parse(value: &str) -> Result<Metric, PuzzleError> {
+        match value {
+            "manhattan" => Ok(Metric::Manhattan),
+            "euclidean" => Ok(Metric::Euclidean),
+            _ => Err(PuzzleError::usage(format!(
+                "Invalid metric '{value}': must be manhattan or euclidean"
+            ))),
+        }
+    }
+
+    pub fn distance(&self, from: Point, to: Point) -> f64 {
+        let dx = (i64::from(from.0) - i64::from(to.0)).abs();
+        let dy = (i64::from(from.1) - i64::from(to.1)).abs();
+        match self {
+            Metric::Manhattan => (dx + dy) as f64,
+            Metric::Euclidean => (dx as f64).hypot(dy as f64),
+        }
+    }
+}
+
+/// A left location matched with a right one, by their position in the input.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Pairing {
+    pub left: usize,
+    pub right: usize,
+    pub from: Point,
+    pub to: Point,
+    pub distance: f64,
+}
+
+/// A matching of every left location with a distinct right one.
+#[derive(Debug, PartialEq)]
+pub struct Assignment {
+    metric: Metric,
+    pairs: Vec<Pairing>,
+    total: f64,
+}
+
+impl Assignment {
+    /// The matching of minimum total distance between `left` and `right`,
+    /// which must be of the same length. Runs in O(n³).
+    pub fn optimal(left: &[Point], right: &[Point], metric: Metric) -> Assignment {
+        assert_eq!(left.len(), right.len(), "sides must be of the same size");
+
+        let cost: Vec<Vec<f64>> = left
+            .iter()
+            .map(|&from| right.iter().map(|&to| metric.distance(from, to)).collect())
+            .collect();
+
+        let pairs: Vec<Pairing> = hungarian(&cost)
+            .into_iter()
+            .enumerate()
+            .map(|(l, r)| Pairing {
+                left: l,
+                right: r,
+                from: left[l],
+                to: right[r],
+                distance: cost[l][r],
+            })
+            .collect();
+        let total = pairs.iter().map(|p| p.distance).sum();
+
+        Assignment {
+            metric,
+            pairs,
+            total,
+        }
+    }
+
+    /// Pairs in the order of the left locations.
+    pub fn pairs(&self) -> &[Pairing] {
+        &self.pairs
+    }
+
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    /// One row per pair, with the positions, the coordinates and the
+    /// distance.
+    pub fn to_csv(&self) -> String {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        writer
+            .write_record([
+                "left", "left_x", "left_y", "right", "right_x", "right_y", "distance",
+            ])
+            .expect("writing to memory");
+
+        for p in &self.pairs {
+            writer
+                .write_record([
+                    p.left.to_string(),
+                    p.from.0.to_string(),
+                    p.from.1.to_string(),
+                    p.right.to_string(),
+                    p.to.0.to_string(),
+                    p.to.1.to_string(),
+                    p.distance.to_string(),
+                ])
+                .expect("writing to memory");
+        }
+
+        let bytes = writer.into_inner().expect("writing to memory");
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl fmt::Display for Assignment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for p in &self.pairs {
+            writeln!(
+                f,
+                "{} ({}, {}) -> {} ({}, {}): {}",
+                p.left, p.from.0, p.from.1, p.right, p.to.0, p.to.1, p.distance
+            )?;
+        }
+        let metric = match self.metric {
+            Metric::Manhattan => "Manhattan",
+            Metric::Euclidean => "Euclidean",
+        };
+        writeln!(f, "Total {metric} distance is: {}", self.total)
+    }
+}
+
+// Hungarian algorithm on a square cost matrix, with row and column
+// potentials: returns the column assigned to every row.
+fn hungarian(cost: &[Vec<f64>]) -> Vec<usize> {
+    let n = cost.len();
+    // index 0 stands for "no row" / a virtual column, hence the one-based
+    // rows and columns
+    let mut row_potential = vec![0.0; n + 1];
+    let mut column_potential = vec![0.0; n + 1];
+    let mut row_of_column = vec![0; n + 1];
+    let mut previous_column = vec![0; n + 1];
+
+    for row in 1..=n {
+        row_of_column[0] = row;
+        let mut column = 0;
+        let mut min_slack = vec![f64::INFINITY; n + 1];
+        let mut visited = vec![false; n + 1];
+
+        // grow an alternating tree until it reaches a free column
+        loop {
+            visited[column] = true;
+            let current_row = row_of_column[column];
+            let mut delta = f64::INFINITY;
+            let mut next_column = 0;
+
+            for j in 1..=n {
+                if visited[j] {
+                    continue;
+                }
+                let slack =
+                    cost[current_row - 1][j - 1] - row_potential[current_row] - column_potential[j];
+                if slack < min_slack[j] {
+                    min_slack[j] = slack;
+                    previous_column[j] = column;
+                }
+                if min_slack[j] < delta {
+                    delta = min_slack[j];
+                    next_column = j;
+                }
+            }
+
+            for j in 0..=n {
+                if visited[j] {
+                    row_potential[row_of_column[j]] += delta;
+                    column_potential[j] -= delta;
+                } else {
+                    min_slack[j] -= delta;
+                }
+            }
+
+            column = next_column;
+            if row_of_column[column] == 0 {
+                break;
+            }
+        }
+
+        // flip the augmenting path
+        while column != 0 {
+            let previous = previous_column[column];
+            row_of_column[column] = row_of_column[previous];
+            column = previous;
+        }
+    }
+
+    let mut column_of_row = vec![0; n];
+    for column in 1..=n {
+        column_of_row[row_of_column[column] - 1] = column - 1;
+    }
+    column_of_row
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Minimum total over every permutation, for small sides
+    fn brute_force(left: &[Point], right: &[Point], metric: Metric) -> f64 {
+        fn visit(
+            row: usize,
+            used: &mut Vec<bool>,
+            total: f64,
+            cost: &dyn Fn(usize, usize) -> f64,
+            best: &mut f64,
+        ) {
+            if row == used.len() {
+                *best = best.min(total);
+                return;
+            }
+            for column in 0..used.len() {
+                if !used[column] {
+                    used[column] = true;
+                    visit(row + 1, used, total + cost(row, column), cost, best);
+                    used[column] = false;
+                }
+            }
+        }
+
+        let cost = |l: usize, r: usize| metric.distance(left[l], right[r]);
+        let mut best = f64::INFINITY;
+        visit(0, &mut vec![false; left.len()], 0.0, &cost, &mut best);
+        best
+    }
+
+    #[test]
+    fn sorted_pairing_is_not_optimal_in_2d() {
+        let left = [(0, 0), (10, 10)];
+        let right = [(10, 11), (1, 0)];
+
+        let assignment = Assignment::optimal(&left, &right, Metric::Manhattan);
+        let pairs: Vec<(usize, usize)> = assignment
+            .pairs()
+            .iter()
+            .map(|p| (p.left, p.right))
+            .collect();
+        assert_eq!(pairs, [(0, 1), (1, 0)]);
+        assert_eq!(assignment.total(), 2.0);
+
+        let assignment = Assignment::optimal(&left, &right, Metric::Euclidean);
+        assert_eq!(assignment.total(), 2.0);
+    }
+
+    #[test]
+    fn optimal_matches_brute_force() {
+        // small deterministic pseudo-random point sets
+        let mut seed: u32 = 12345;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            ((seed >> 16) % 21) as i32 - 10
+        };
+
+        for size in 1..=6 {
+            for _ in 0..20 {
+                let left: Vec<Point> = (0..size).map(|_| (next(), next())).collect();
+                let right: Vec<Point> = (0..size).map(|_| (next(), next())).collect();
+
+                for metric in [Metric::Manhattan, Metric::Euclidean] {
+                    let assignment = Assignment::optimal(&left, &right, metric);
+                    let expected = brute_force(&left, &right, metric);
+                    assert!((assignment.total() - expected).abs() < 1e-9);
+
+                    let mut rights: Vec<usize> =
+                        assignment.pairs().iter().map(|p| p.right).collect();
+                    rights.sort_unstable();
+                    assert_eq!(rights, (0..size).collect::<Vec<_>>());
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn assignment_output() {
+        let assignment = Assignment::optimal(&[(0, 0)], &[(3, 4)], Metric::Euclidean);
+        assert_eq!(
+            assignment.to_string(),
+            "0 (0, 0) -> 0 (3, 4): 5\nTotal Euclidean distance is: 5\n"
+        );
+        assert_eq!(
+            assignment.to_csv(),
+            "left,left_x,left_y,right,right_x,right_y,distance\n0,0,0,0,3,4,5\n"
+        );
+    }
+}
@@ -1,110 +1,495 @@
-use csv::Reader;
-use serde::de::DeserializeOwned;
-use std::io::{self, BufReader, Read};
-use std::{error::Error, fs::File, process};
+mod assignment;
 
-#[derive(Debug, serde::Deserialize)]
-struct Entry {
-    left_list: i32,
-    right_list: i32,
+pub use assignment::{Assignment, Metric, Pairing, Point};
+
+use csv::{ReaderBuilder, StringRecord};
+use flate2::read::MultiGzDecoder;
+use puzzle::{PuzzleError, Solution};
+use std::cmp::Ordering;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, Read};
+
+/// Location ID lists, one per column of the input.
+#[derive(Debug, Clone)]
+pub struct Lists {
+    names: Vec<String>,
+    lists: Vec<Vec<i32>>,
+}
+
+impl Lists {
+    fn with_names(names: Vec<String>) -> Result<Lists, PuzzleError> {
+        if names.len() < 2 {
+            return Err(PuzzleError::at(
+                1,
+                1,
+                format!("expected at least 2 columns, found {}", names.len()),
+            ));
+        }
+
+        let lists = vec![Vec::new(); names.len()];
+        Ok(Lists { names, lists })
+    }
+
+    /// The lists sorted in place, without a copy of them: rows no longer
+    /// pair up afterwards.
+    pub fn into_sorted(mut self) -> SortedLists {
+        for list in &mut self.lists {
+            list.sort_unstable();
+        }
+
+        SortedLists {
+            names: self.names,
+            lists: self.lists,
+        }
+    }
+
+    /// The left and right locations of inputs made of four columns: the x
+    /// and y coordinates of each side.
+    pub fn points(&self) -> Result<(Vec<Point>, Vec<Point>), PuzzleError> {
+        match self.lists.as_slice() {
+            [left_x, left_y, right_x, right_y] => {
+                let zip = |x: &[i32], y: &[i32]| x.iter().copied().zip(y.iter().copied()).collect();
+                Ok((zip(left_x, left_y), zip(right_x, right_y)))
+            }
+            lists => Err(PuzzleError::invalid(format!(
+                "expected 4 columns with the x and y of each side, found {}",
+                lists.len()
+            ))),
+        }
+    }
+}
+
+/// Location ID lists with every list sorted, which is all the distance and
+/// the similarity need.
+#[derive(Debug, Clone)]
+pub struct SortedLists {
+    names: Vec<String>,
+    lists: Vec<Vec<i32>>,
+}
+
+impl SortedLists {
+    /// Total distance between every ordered pair of lists.
+    pub fn distance_matrix(&self) -> Matrix {
+        self.matrix(Measure::Distance, |i, j| {
+            distance_of_sorted(&self.lists[i], &self.lists[j])
+        })
+    }
+
+    /// Similarity score of every list with respect to every other one.
+    pub fn similarity_matrix(&self) -> Matrix {
+        self.matrix(Measure::Similarity, |i, j| {
+            similarity_of_sorted(&self.lists[i], &self.lists[j])
+        })
+    }
+
+    fn matrix(&self, measure: Measure, value: impl Fn(usize, usize) -> i64) -> Matrix {
+        let size = self.lists.len();
+        Matrix {
+            measure,
+            names: self.names.clone(),
+            values: (0..size)
+                .map(|i| (0..size).map(|j| value(i, j)).collect())
+                .collect(),
+        }
+    }
+}
+
+/// What a matrix compares lists by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Measure {
+    Distance,
+    Similarity,
+}
+
+impl Measure {
+    fn parse(value: &str) -> Result<Measure, PuzzleError> {
+        match value {
+            "distance" => Ok(Measure::Distance),
+            "similarity" => Ok(Measure::Similarity),
+            _ => Err(PuzzleError::usage(format!(
+                "Invalid matrix '{value}': must be distance or similarity"
+            ))),
+        }
+    }
+}
+
+impl fmt::Display for Measure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Measure::Distance => write!(f, "distance"),
+            Measure::Similarity => write!(f, "similarity"),
+        }
+    }
+}
+
+/// A measure for every ordered pair of lists: row `i`, column `j` compares
+/// list `i` with list `j`.
+#[derive(Debug, PartialEq)]
+pub struct Matrix {
+    measure: Measure,
+    names: Vec<String>,
+    values: Vec<Vec<i64>>,
+}
+
+impl Matrix {
+    pub fn get(&self, row: usize, column: usize) -> i64 {
+        self.values[row][column]
+    }
+
+    /// The matrix as CSV, with the list names as header row and first
+    /// column.
+    pub fn to_csv(&self) -> String {
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let mut header = vec![self.measure.to_string()];
+        header.extend(self.names.iter().cloned());
+        writer.write_record(&header).expect("writing to memory");
+
+        for (name, row) in self.names.iter().zip(&self.values) {
+            let mut record = vec![name.clone()];
+            record.extend(row.iter().map(|value| value.to_string()));
+            writer.write_record(&record).expect("writing to memory");
+        }
+
+        let bytes = writer.into_inner().expect("writing to memory");
+        String::from_utf8_lossy(&bytes).into_owned()
+    }
+}
+
+impl fmt::Display for Matrix {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let label_width = self
+            .names
+            .iter()
+            .map(|name| name.chars().count())
+            .chain([self.measure.to_string().len()])
+            .max()
+            .unwrap_or_default();
+        let widths: Vec<usize> = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(j, name)| {
+                self.values
+                    .iter()
+                    .map(|row| row[j].to_string().len())
+                    .chain([name.chars().count()])
+                    .max()
+                    .unwrap_or_default()
+            })
+            .collect();
+
+        write!(f, "{:<label_width$}", self.measure.to_string())?;
+        for (name, width) in self.names.iter().zip(&widths) {
+            write!(f, "  {name:>width$}")?;
+        }
+        writeln!(f)?;
+
+        for (name, row) in self.names.iter().zip(&self.values) {
+            write!(f, "{name:<label_width$}")?;
+            for (value, width) in row.iter().zip(&widths) {
+                write!(f, "  {value:>width$}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// What to compute from the lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The answers of the puzzle, from the first two lists.
+    Answers,
+    /// A measure between every ordered pair of lists.
+    Matrix(Measure),
+    /// The optimal matching of 2-D locations.
+    Assignment(Metric),
 }
 
 #[derive(Debug)]
 pub struct Config {
     puzzle_input: String,
+    mode: Mode,
 }
 
 impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
         if args.len() < 2 {
-            return Err("Not enough arguments");
+            return Err(PuzzleError::usage("Not enough arguments"));
         }
 
         let puzzle_input = args[1].clone();
 
-        Ok(Config { puzzle_input })
+        let mode = match &args[2..] {
+            [] => Mode::Answers,
+            [option, measure] if option == "--matrix" => Mode::Matrix(Measure::parse(measure)?),
+            [option, metric] if option == "--assign" => Mode::Assignment(Metric::parse(metric)?),
+            _ => {
+                return Err(PuzzleError::usage(
+                    "Usage: day1 <input> [--matrix <distance|similarity> | \
+                     --assign <manhattan|euclidean>]",
+                ))
+            }
+        };
+
+        Ok(Config { puzzle_input, mode })
     }
-}
 
-fn deserialize<T, R>(reader: R) -> Result<Vec<T>, Box<dyn std::error::Error>>
-where
-    T: std::fmt::Debug + DeserializeOwned,
-    R: Read,
-{
-    let mut rdr = Reader::from_reader(reader);
-    let mut structs: Vec<T> = Vec::new();
-    for result in rdr.deserialize() {
-        let record: T = result?;
-        structs.push(record);
+    pub fn mode(&self) -> Mode {
+        self.mode
     }
+}
 
-    Ok(structs)
+/// Layouts accepted for the location lists.
+#[derive(Debug, PartialEq)]
+enum InputFormat {
+    /// CSV with a header row naming the lists.
+    Csv,
+    /// Whitespace-separated columns, as downloaded from the puzzle page.
+    Columns,
 }
 
-fn get_total_distance_from_raw_data(raw_list: &Vec<Entry>) -> i32 {
-    let mut first = Vec::new();
-    let mut second = Vec::new();
+impl InputFormat {
+    fn detect(raw_content: &str) -> InputFormat {
+        let first_line = raw_content.lines().find(|l| !l.trim().is_empty());
+        match first_line {
+            Some(line) if line.contains(',') => InputFormat::Csv,
+            _ => InputFormat::Columns,
+        }
+    }
+}
 
-    let _ = raw_list
+fn parse_csv<R: Read>(reader: R) -> Result<Lists, PuzzleError> {
+    let mut rdr = ReaderBuilder::new().flexible(true).from_reader(reader);
+    let names = rdr
+        .headers()
+        .map_err(PuzzleError::from_csv)?
         .iter()
-        .map(|x| {
-            first.push(x.left_list);
-            second.push(x.right_list);
-        })
-        .collect::<Vec<_>>();
+        .map(|name| name.trim().to_string())
+        .collect();
+    let mut lists = Lists::with_names(names)?;
 
-    first.sort();
-    second.sort();
+    let mut record = StringRecord::new();
+    while rdr
+        .read_record(&mut record)
+        .map_err(PuzzleError::from_csv)?
+    {
+        let line = record.position().map_or(1, |p| p.line() as usize);
+        if record.len() != lists.names.len() {
+            return Err(PuzzleError::at(
+                line,
+                1,
+                format!(
+                    "expected {} columns, found {}",
+                    lists.names.len(),
+                    record.len()
+                ),
+            ));
+        }
 
-    let length = raw_list.len();
+        for (index, (field, list)) in record.iter().zip(&mut lists.lists).enumerate() {
+            let value = field.trim().parse().map_err(|_| {
+                PuzzleError::at(line, index + 1, format!("invalid number '{field}'"))
+            })?;
+            list.push(value);
+        }
+    }
 
-    assert_eq!(first.len(), length);
-    assert_eq!(second.len(), length);
+    Ok(lists)
+}
 
-    let mut total_distance = 0;
-    for ix in 0..length {
-        let left = first[ix];
-        let right = second[ix];
+fn parse_columns<R: BufRead>(reader: R, file: &str) -> Result<Lists, PuzzleError> {
+    let mut lists: Option<Lists> = None;
+    for (index, line) in reader.lines().enumerate() {
+        let line = line.map_err(|source| io_error(file, source))?;
+        if line.trim().is_empty() {
+            continue;
+        }
 
-        let diff = right - left;
+        let values: Vec<&str> = line.split_whitespace().collect();
+        let lists = match &mut lists {
+            Some(lists) => lists,
+            None => {
+                let names = (1..=values.len()).map(|n| format!("list{n}")).collect();
+                lists.insert(Lists::with_names(names).map_err(|e| e.on_line(index + 1))?)
+            }
+        };
 
-        // Need to account for absolute distances
-        total_distance += diff.abs();
+        if values.len() != lists.names.len() {
+            return Err(PuzzleError::at(
+                index + 1,
+                1,
+                format!(
+                    "expected {} columns, found {}",
+                    lists.names.len(),
+                    values.len()
+                ),
+            ));
+        }
+
+        for (token, list) in values.into_iter().zip(&mut lists.lists) {
+            list.push(puzzle::parse_number(&line, token).map_err(|e| e.on_line(index + 1))?);
+        }
     }
 
-    total_distance
+    match lists {
+        Some(lists) => Ok(lists),
+        None => Lists::with_names(Vec::new()),
+    }
 }
 
-fn get_total_similarity_score_from_raw_data(raw_list: &Vec<Entry>) -> i32 {
-    let mut first = Vec::new();
-    let mut second = Vec::new();
+fn io_error(file: &str, source: io::Error) -> PuzzleError {
+    PuzzleError::Io {
+        file: file.to_string(),
+        source,
+    }
+}
+
+/// Reads the lists one line at a time, so that only the columns are kept
+/// in memory. `file` names the input in I/O errors.
+fn read_lists<R: BufRead>(mut reader: R, file: &str) -> Result<Lists, PuzzleError> {
+    // the layout is told by the first non-blank line: read up to it, then
+    // hand what was read back to the parser in front of the rest
+    let mut head = String::new();
+    loop {
+        let start = head.len();
+        let read = reader
+            .read_line(&mut head)
+            .map_err(|source| io_error(file, source))?;
+        if read == 0 || !head[start..].trim().is_empty() {
+            break;
+        }
+    }
+
+    let format = InputFormat::detect(&head);
+    let reader = io::Cursor::new(head).chain(reader);
+    match format {
+        InputFormat::Csv => parse_csv(reader),
+        InputFormat::Columns => parse_columns(reader, file),
+    }
+}
 
-    let _ = raw_list
+fn get_lists(raw_content: &str) -> Result<Lists, PuzzleError> {
+    // reading from memory cannot fail
+    read_lists(raw_content.as_bytes(), "")
+}
+
+// Wraps gzip streams, told by their magic number, in a decoder
+fn decompress<'a>(mut reader: Box<dyn BufRead + 'a>) -> io::Result<Box<dyn BufRead + 'a>> {
+    if reader.fill_buf()?.starts_with(&[0x1f, 0x8b]) {
+        Ok(Box::new(BufReader::new(MultiGzDecoder::new(reader))))
+    } else {
+        Ok(reader)
+    }
+}
+
+// Name of the input in messages
+fn input_name(file: &str) -> &str {
+    if file == "-" {
+        "<stdin>"
+    } else {
+        file
+    }
+}
+
+/// Opens `file`, or stdin for `-`, decompressing gzip inputs on the fly.
+fn open_input(file: &str) -> Result<Box<dyn BufRead>, PuzzleError> {
+    let name = input_name(file);
+    let reader: Box<dyn BufRead> = if file == "-" {
+        Box::new(io::stdin().lock())
+    } else {
+        let f = File::open(file).map_err(|source| io_error(name, source))?;
+        Box::new(BufReader::new(f))
+    };
+
+    decompress(reader).map_err(|source| io_error(name, source))
+}
+
+fn load_lists(file: &str) -> Result<Lists, PuzzleError> {
+    let name = input_name(file);
+    read_lists(open_input(file)?, name).map_err(|e| e.in_file(name))
+}
+
+// Need to account for absolute distances, computed in 64 bits as the
+// difference of two i32 may not fit in an i32
+fn distance_of_sorted(first: &[i32], second: &[i32]) -> i64 {
+    first
         .iter()
-        .map(|x| {
-            first.push(x.left_list);
-            second.push(x.right_list);
-        })
-        .collect::<Vec<_>>();
+        .zip(second)
+        .map(|(&left, &right)| (i64::from(right) - i64::from(left)).abs())
+        .sum()
+}
+
+// Every value of `first` times its occurrences in `second`, walking both
+// sorted lists side by side one run of equal values at a time
+fn similarity_of_sorted(first: &[i32], second: &[i32]) -> i64 {
+    let (mut i, mut j) = (0, 0);
+    let mut total = 0;
+    while i < first.len() && j < second.len() {
+        match first[i].cmp(&second[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                let value = first[i];
+                let left = first[i..].iter().take_while(|&&x| x == value).count();
+                let right = second[j..].iter().take_while(|&&x| x == value).count();
+                total += i64::from(value) * (left * right) as i64;
+                i += left;
+                j += right;
+            }
+        }
+    }
+    total
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    type Input = SortedLists;
+    type Answer = i64;
+
+    fn parse(raw_input: &str) -> Result<SortedLists, PuzzleError> {
+        get_lists(raw_input).map(Lists::into_sorted)
+    }
+
+    // the first two lists are the ones of the puzzle
+    fn part1(input: &SortedLists) -> i64 {
+        distance_of_sorted(&input.lists[0], &input.lists[1])
+    }
 
-    let mut score = 0;
-    for x in first {
-        let occurrences = second.iter().filter(|&n| *n == x).count();
-        score += x * occurrences as i32;
+    fn part2(input: &SortedLists) -> i64 {
+        similarity_of_sorted(&input.lists[0], &input.lists[1])
     }
+}
+
+/// Solves both parts, streaming the input; `-` reads it from stdin.
+pub fn run(config: Config) -> Result<(i64, i64), PuzzleError> {
+    let lists = load_lists(&config.puzzle_input)?.into_sorted();
 
-    score
+    Ok((Day1::part1(&lists), Day1::part2(&lists)))
 }
 
-pub fn run(config: Config) -> Result<(i32, i32), Box<dyn Error>> {
-    let file = File::open(config.puzzle_input)?;
-    let reader = BufReader::new(file);
+/// The matrix of `measure` between every ordered pair of lists.
+pub fn run_matrix(config: Config, measure: Measure) -> Result<Matrix, PuzzleError> {
+    let lists = load_lists(&config.puzzle_input)?.into_sorted();
 
-    let structs: Vec<Entry> = deserialize(reader)?;
-    let total_distance = get_total_distance_from_raw_data(&structs);
-    let similarity_score = get_total_similarity_score_from_raw_data(&structs);
+    Ok(match measure {
+        Measure::Distance => lists.distance_matrix(),
+        Measure::Similarity => lists.similarity_matrix(),
+    })
+}
+
+/// The matching of minimum total `metric` distance between the left and
+/// right locations of a four-column input.
+pub fn run_assignment(config: Config, metric: Metric) -> Result<Assignment, PuzzleError> {
+    let lists = load_lists(&config.puzzle_input)?;
+    let (left, right) = lists
+        .points()
+        .map_err(|e| e.in_file(input_name(&config.puzzle_input)))?;
 
-    Ok((total_distance, similarity_score))
+    Ok(Assignment::optimal(&left, &right, metric))
 }
 
 // Note on printing during tests:
@@ -113,8 +498,6 @@ pub fn run(config: Config) -> Result<(i32, i32), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use io::BufReader;
-
     use super::*;
 
     #[test]
@@ -128,8 +511,8 @@ left_list,right_list
 3,9
 3,3
 ";
-        let structs: Vec<Entry> = deserialize(data.as_bytes()).unwrap();
-        let total_distance = get_total_distance_from_raw_data(&structs);
+        let lists = get_lists(data).unwrap().into_sorted();
+        let total_distance = Day1::part1(&lists);
         assert_eq!(total_distance, 11);
     }
 
@@ -144,8 +527,201 @@ left_list,right_list
 3,9
 3,3
 ";
-        let structs: Vec<Entry> = deserialize(data.as_bytes()).unwrap();
-        let total_similarity_score = get_total_similarity_score_from_raw_data(&structs);
+        let lists = get_lists(data).unwrap().into_sorted();
+        let total_similarity_score = Day1::part2(&lists);
         assert_eq!(total_similarity_score, 31);
     }
+
+    #[test]
+    fn day1_totals_do_not_overflow() {
+        let left = vec![-2_000_000_000; 3];
+        let right = vec![2_000_000_000; 3];
+        assert_eq!(distance_of_sorted(&left, &right), 12_000_000_000);
+
+        let list = vec![2_000_000_000; 2];
+        assert_eq!(similarity_of_sorted(&list, &list), 8_000_000_000);
+    }
+
+    #[test]
+    fn day1_similarity_of_sorted_runs() {
+        // 3 occurs twice on each side, 5 only on the left
+        assert_eq!(
+            similarity_of_sorted(&[1, 3, 3, 5], &[1, 2, 3, 3, 4]),
+            1 + 2 * 2 * 3
+        );
+        assert_eq!(similarity_of_sorted(&[-4, -4], &[-4]), -8);
+        assert_eq!(similarity_of_sorted(&[], &[1]), 0);
+    }
+
+    #[test]
+    fn day1_detect_input_format() {
+        assert_eq!(
+            InputFormat::detect("left_list,right_list\n3,4"),
+            InputFormat::Csv
+        );
+        assert_eq!(InputFormat::detect("\n3   4\n4   3"), InputFormat::Columns);
+    }
+
+    #[test]
+    fn day1_native_input_verify() {
+        let raw_content = std::fs::read_to_string("content/sample-input.txt").unwrap();
+        let lists = get_lists(&raw_content).unwrap().into_sorted();
+        assert_eq!(lists.lists[0].len(), 6);
+        assert_eq!(Day1::part1(&lists), 11);
+        assert_eq!(Day1::part2(&lists), 31);
+    }
+
+    #[test]
+    fn day1_solution_verify() {
+        let data = "\
+3   4
+4   3
+2   5
+1   3
+3   9
+3   3
+";
+        assert_eq!(Day1::solve(data).unwrap(), (11, 31));
+    }
+
+    #[test]
+    fn day1_native_input_invalid_line() {
+        let data = "\
+3   4
+4   3   7
+";
+        let error = get_lists(data).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected 2 columns, found 3"
+        );
+
+        let error = get_lists("3   4\n4   x3\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 5: invalid number 'x3'");
+    }
+
+    #[test]
+    fn day1_pairwise_matrices() {
+        let data = "\
+3   4   3
+4   3   3
+2   5   1
+1   3   9
+3   9   4
+3   3   7
+";
+        let lists = get_lists(data).unwrap().into_sorted();
+        assert_eq!(lists.names, ["list1", "list2", "list3"]);
+
+        let distance = lists.distance_matrix();
+        assert_eq!(distance.get(0, 1), 11);
+        assert_eq!(distance.get(1, 0), 11);
+        assert_eq!(distance.get(2, 2), 0);
+        assert_eq!(distance.get(0, 2), 11);
+
+        let similarity = lists.similarity_matrix();
+        assert_eq!(similarity.get(0, 1), 31);
+        assert_eq!(similarity.get(1, 0), 4 + 3 * 3 * 3);
+        assert_eq!(similarity.get(0, 2), 3 * 2 + 4 + 1 + 3 * 2 + 3 * 2);
+        assert_eq!(Day1::solve(data).unwrap(), (11, 31));
+    }
+
+    #[test]
+    fn day1_matrix_output() {
+        let data = "\
+north,south,east
+3,4,3
+4,3,3
+";
+        let matrix = get_lists(data).unwrap().into_sorted().distance_matrix();
+        assert_eq!(
+            matrix.to_csv(),
+            "distance,north,south,east\nnorth,0,0,1\nsouth,0,0,1\neast,1,1,0\n"
+        );
+        assert_eq!(
+            matrix.to_string(),
+            "\
+distance  north  south  east
+north         0      0     1
+south         0      0     1
+east          1      1     0
+"
+        );
+    }
+
+    #[test]
+    fn day1_inconsistent_columns() {
+        let error = get_lists("3   4   5\n4   3\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected 3 columns, found 2"
+        );
+
+        let error = get_lists("3\n4\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 1, column 1: expected at least 2 columns, found 1"
+        );
+
+        let error = get_lists("a,b\n3,4\n4,x\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 3, column 2: invalid number 'x'");
+    }
+
+    #[test]
+    fn day1_gzip_input_verify() {
+        use flate2::write::GzEncoder;
+        use flate2::Compression;
+        use std::io::Write;
+
+        let raw_content = std::fs::read_to_string("content/sample-input.txt").unwrap();
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(raw_content.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let reader = decompress(Box::new(compressed.as_slice())).unwrap();
+        let lists = read_lists(reader, "sample-input.txt.gz")
+            .unwrap()
+            .into_sorted();
+        assert_eq!((Day1::part1(&lists), Day1::part2(&lists)), (11, 31));
+
+        // plain inputs go through untouched
+        let reader = decompress(Box::new(raw_content.as_bytes())).unwrap();
+        let lists = read_lists(reader, "sample-input.txt")
+            .unwrap()
+            .into_sorted();
+        assert_eq!(Day1::part1(&lists), 11);
+    }
+
+    #[test]
+    fn day1_stream_keeps_line_numbers() {
+        let error = get_lists("\n\nleft,right\n3,4\n4,x\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 5, column 2: invalid number 'x'");
+
+        let error = get_lists("\n3   4\n\n4   x\n").unwrap_err();
+        assert_eq!(error.to_string(), "line 4, column 5: invalid number 'x'");
+    }
+
+    #[test]
+    fn day1_assignment_mode() {
+        let config = Config::build(&["day1", "in.txt", "--assign", "euclidean"].map(String::from));
+        assert_eq!(config.unwrap().mode(), Mode::Assignment(Metric::Euclidean));
+        assert!(
+            Config::build(&["day1", "in.txt", "--assign", "chebyshev"].map(String::from)).is_err()
+        );
+
+        let lists = get_lists("0 0 10 11\n10 10 1 0\n").unwrap();
+        let (left, right) = lists.points().unwrap();
+        assert_eq!(left, [(0, 0), (10, 10)]);
+        assert_eq!(right, [(10, 11), (1, 0)]);
+        assert_eq!(
+            Assignment::optimal(&left, &right, Metric::Manhattan).total(),
+            2.0
+        );
+
+        let error = get_lists("3   4\n").unwrap().points().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "expected 4 columns with the x and y of each side, found 2"
+        );
+    }
 }
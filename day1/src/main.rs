@@ -1,11 +1,59 @@
-use std::{env, error};
+use puzzle::output::{Format, Output};
+use puzzle::PuzzleError;
+use std::env;
+use std::time::Instant;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    let args: Vec<String> = env::args().collect();
-    let config = day1::Config::build(&args)?;
-    let (total_distance, similarity_score) = day1::run(config)?;
+// Tables are printed as text, or as CSV when asked for
+fn print_table(output: &Output, text: &str, csv: &str) {
+    match output.format() {
+        Format::Csv => print!("{csv}"),
+        _ => print!("{text}"),
+    }
+}
+
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let output = Output::from_args(&mut args);
+    let input = args.get(1).cloned();
+
+    let config = day1::Config::build(&args)
+        .unwrap_or_else(|err| output.fail(1, input.as_deref(), "Problem parsing arguments", &err));
+
+    if config.mode() != day1::Mode::Answers && output.format() == Format::Json {
+        let err = PuzzleError::usage("--matrix and --assign only support the text and csv formats");
+        output.fail(1, input.as_deref(), "Problem parsing arguments", &err);
+    }
+
+    match config.mode() {
+        day1::Mode::Answers => (),
+        day1::Mode::Matrix(measure) => {
+            let matrix = day1::run_matrix(config, measure)
+                .unwrap_or_else(|err| output.fail(1, input.as_deref(), "Application error", &err));
+            print_table(&output, &matrix.to_string(), &matrix.to_csv());
+            return;
+        }
+        day1::Mode::Assignment(metric) => {
+            let assignment = day1::run_assignment(config, metric)
+                .unwrap_or_else(|err| output.fail(1, input.as_deref(), "Application error", &err));
+            print_table(&output, &assignment.to_string(), &assignment.to_csv());
+            return;
+        }
+    }
+
+    let start = Instant::now();
+    let (total_distance, similarity_score) = day1::run(config)
+        .unwrap_or_else(|err| output.fail(1, input.as_deref(), "Application error", &err));
+    let elapsed = start.elapsed();
 
-    println!("Total distance is: {total_distance}");
-    println!("Total similarity score is: {similarity_score}");
-    Ok(())
+    if output.is_text() {
+        println!("Total distance is: {total_distance}");
+        println!("Total similarity score is: {similarity_score}");
+    } else {
+        output.answers(
+            1,
+            input.as_deref().unwrap_or_default(),
+            &[&total_distance, &similarity_score],
+            elapsed,
+        );
+    }
 }
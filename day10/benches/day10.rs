@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day10::Day10;
+use puzzle::bench;
+
+fn day10(c: &mut Criterion) {
+    let raw_input = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/puzzle-input.txt"
+    ));
+    bench::phases::<Day10>(c, "day10", &raw_input);
+}
+
+criterion_group!(benches, day10);
+criterion_main!(benches);
@@ -1,8 +1,7 @@
+use grid::Grid;
+use puzzle::{PuzzleError, Solution};
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::fs;
-use std::io::{self, Read};
-use std::{error::Error, fs::File, process};
 
 #[derive(Debug)]
 pub struct Config {
@@ -10,9 +9,9 @@ pub struct Config {
 }
 
 impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
         if args.len() < 2 {
-            return Err("Not enough arguments");
+            return Err(PuzzleError::usage("Not enough arguments"));
         }
 
         let puzzle_input = args[1].clone();
@@ -21,67 +20,41 @@ impl Config {
     }
 }
 
+#[allow(dead_code)]
 struct TrailPosition {
     height: u32,
     position: (usize, usize),
-    next: Option<Vec<Box<TrailPosition>>>,
+    next: Option<Vec<TrailPosition>>,
 }
 
-struct TopographicMap {
-    positions: Vec<Vec<char>>,
-    x_max: usize,
-    y_max: usize,
+type HikingTrail = Vec<(usize, usize)>;
+
+#[derive(Clone)]
+pub struct TopographicMap {
+    positions: Grid<u32>,
     trailheads: Vec<(usize, usize)>,
     scores: HashMap<(usize, usize), HashSet<(usize, usize)>>,
-    ratings: HashMap<(usize, usize), HashSet<Vec<(usize, usize)>>>,
+    ratings: HashMap<(usize, usize), HashSet<HikingTrail>>,
 }
 
 impl TopographicMap {
-    pub fn make(raw_data: &str) -> Result<TopographicMap, &'static str> {
-        let positions: Vec<Vec<char>> = raw_data
-            .trim()
-            .split("\n")
-            .map(|s| s.chars().collect())
-            .collect();
-
-        if positions.len() == 0 {
-            return Err("No lines read from raw content.");
-        }
-
-        if positions[0].len() == 0 {
-            return Err("Read empty line.");
-        }
-
-        let y_max = positions.len();
-        let x_max = positions[0].len();
+    pub fn make(raw_data: &str) -> Result<TopographicMap, PuzzleError> {
+        let positions = Grid::parse(raw_data, |c| c.to_digit(10))?;
 
         Ok(TopographicMap {
             positions,
-            x_max,
-            y_max,
             trailheads: Vec::new(),
             scores: HashMap::new(),
             ratings: HashMap::new(),
         })
     }
 
-    fn is_position_valid(&self, position: (i32, i32)) -> bool {
-        position.0 >= 0
-            && usize::try_from(position.0).unwrap() < self.x_max
-            && position.1 >= 0
-            && usize::try_from(position.1).unwrap() < self.y_max
-    }
-
     fn is_expected_height_at_position(
         &self,
         position: &(usize, usize),
         expected_height: u32,
     ) -> bool {
-        assert!(position.0 < self.x_max);
-        assert!(position.1 < self.y_max);
-
-        let actual_height = self.positions[position.1][position.0].to_digit(10).unwrap();
-        actual_height == expected_height
+        self.positions[*position] == expected_height
     }
 
     fn get_next_height(&self, current_height: u32) -> u32 {
@@ -89,18 +62,12 @@ impl TopographicMap {
     }
 
     fn compute_trailheads(&mut self) {
-        for (y, line) in self.positions.iter().enumerate() {
-            for (x, c) in line.iter().enumerate() {
-                if *c == '0' {
-                    self.trailheads.push((x, y));
-                }
-            }
-        }
+        self.trailheads = self.positions.find_all(&0).collect();
 
         let trailheads = self.trailheads.clone();
         for th in trailheads.iter() {
             let hiking_trail = vec![*th];
-            let ht = self.compute_hiking_trail_recursive(*th, hiking_trail, *th, None, 0);
+            let _ht = self.compute_hiking_trail_recursive(*th, hiking_trail, *th, None, 0);
         }
     }
 
@@ -157,7 +124,7 @@ impl TopographicMap {
 
         let possible_positions = self.get_possible_valid_positions(&current, from, current_height);
 
-        if let None = possible_positions {
+        if possible_positions.is_none() {
             return TrailPosition {
                 height: current_height,
                 position: current,
@@ -180,7 +147,7 @@ impl TopographicMap {
                     self.get_next_height(current_height),
                 );
 
-                trails.push(Box::new(trail));
+                trails.push(trail);
             }
 
             if !trails.is_empty() {
@@ -201,34 +168,9 @@ impl TopographicMap {
         from: Option<(usize, usize)>,
         current_height: u32,
     ) -> Option<Vec<(usize, usize)>> {
-        let candidates: Vec<(i32, i32)> = vec![
-            (
-                i32::try_from(current_pos.0).unwrap() - 1,
-                i32::try_from(current_pos.1).unwrap(),
-            ),
-            (
-                i32::try_from(current_pos.0).unwrap(),
-                i32::try_from(current_pos.1).unwrap() - 1,
-            ),
-            (
-                i32::try_from(current_pos.0).unwrap() + 1,
-                i32::try_from(current_pos.1).unwrap(),
-            ),
-            (
-                i32::try_from(current_pos.0).unwrap(),
-                i32::try_from(current_pos.1 + 1).unwrap(),
-            ),
-        ];
-
-        let candidates: Vec<(usize, usize)> = candidates
-            .iter()
-            .filter(|position| self.is_position_valid(**position))
-            .map(|position| {
-                (
-                    usize::try_from(position.0).unwrap(),
-                    usize::try_from(position.1).unwrap(),
-                )
-            })
+        let candidates: Vec<(usize, usize)> = self
+            .positions
+            .neighbours_4(*current_pos)
             .filter(|position| {
                 let next_height = self.get_next_height(current_height);
                 self.is_expected_height_at_position(position, next_height)
@@ -237,7 +179,7 @@ impl TopographicMap {
                 if let Some(from_position) = from {
                     return from_position != *position;
                 }
-                return true;
+                true
             })
             .collect();
 
@@ -248,21 +190,39 @@ impl TopographicMap {
         Some(candidates)
     }
 
+    #[cfg(test)]
     fn trailheads_num(&self) -> usize {
         self.trailheads.len()
     }
 }
 
-pub fn run(config: Config) -> Result<(u32, u32), Box<dyn Error>> {
-    let raw_content = fs::read_to_string(config.puzzle_input)?;
+pub struct Day10;
+
+impl Solution for Day10 {
+    type Input = TopographicMap;
+    type Answer = u32;
+
+    fn parse(raw_input: &str) -> Result<TopographicMap, PuzzleError> {
+        TopographicMap::make(raw_input)
+    }
+
+    fn part1(input: &TopographicMap) -> u32 {
+        let mut topographic_map = input.clone();
+        topographic_map.compute_trailheads();
+        topographic_map.sum_scores()
+    }
 
-    let mut topographic_map = TopographicMap::make(&raw_content).unwrap();
-    topographic_map.compute_trailheads();
+    fn part2(input: &TopographicMap) -> u32 {
+        let mut topographic_map = input.clone();
+        topographic_map.compute_trailheads();
+        topographic_map.sum_ratings()
+    }
+}
 
-    let tot_score = topographic_map.sum_scores();
-    let tot_ratings = topographic_map.sum_ratings();
+pub fn run(config: Config) -> Result<(u32, u32), PuzzleError> {
+    let raw_content = puzzle::read_input(&config.puzzle_input)?;
 
-    Ok((tot_score, tot_ratings))
+    Day10::solve(&raw_content).map_err(|e| e.in_file(&config.puzzle_input))
 }
 
 // Note on printing during tests:
@@ -271,8 +231,6 @@ pub fn run(config: Config) -> Result<(u32, u32), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use io::BufReader;
-
     use super::*;
 
     #[test]
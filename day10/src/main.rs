@@ -1,13 +1,33 @@
-use std::{env, error};
+use puzzle::output::Output;
+use std::env;
+use std::time::Instant;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    println!("Hello, day 10!");
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let output = Output::from_args(&mut args);
+    let input = args.get(1).cloned();
 
-    let args: Vec<String> = env::args().collect();
-    let config = day10::Config::build(&args)?;
-    let (result, ratings) = day10::run(config)?;
+    if output.is_text() {
+        println!("Hello, day 10!");
+    }
 
-    println!("Total sum of scores:         {result}");
-    println!("Total sum of ratings:        {ratings}");
-    Ok(())
+    let config = day10::Config::build(&args)
+        .unwrap_or_else(|err| output.fail(10, input.as_deref(), "Problem parsing arguments", &err));
+
+    let start = Instant::now();
+    let (result, ratings) = day10::run(config)
+        .unwrap_or_else(|err| output.fail(10, input.as_deref(), "Application error", &err));
+    let elapsed = start.elapsed();
+
+    if output.is_text() {
+        println!("Total sum of scores:         {result}");
+        println!("Total sum of ratings:        {ratings}");
+    } else {
+        output.answers(
+            10,
+            input.as_deref().unwrap_or_default(),
+            &[&result, &ratings],
+            elapsed,
+        );
+    }
 }
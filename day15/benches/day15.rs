@@ -0,0 +1,25 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day15::Day15;
+use puzzle::bench;
+
+fn day15(c: &mut Criterion) {
+    let map = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/puzzle_input_map.txt"
+    ));
+    let moves = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/puzzle_input_moves.txt"
+    ));
+    let raw_input = format!("{}\n\n{moves}", map.trim_end());
+
+    bench::phases::<Day15>(c, "day15", &raw_input);
+}
+
+criterion_group! {
+    name = benches;
+    // every move of both parts is simulated on a copy of the whole map
+    config = Criterion::default().sample_size(10);
+    targets = day15
+}
+criterion_main!(benches);
@@ -1,11 +1,12 @@
-use std::fs;
-use std::io::{self, Read};
-use std::{error::Error, fs::File, process};
+use grid::Grid;
+use puzzle::{PuzzleError, Solution};
 
+/// Either the puzzle input as downloaded, holding the map, a blank line and
+/// the moves, or a map file followed by a moves file.
 #[derive(Debug)]
 pub struct Config {
     puzzle_input_map: String,
-    puzzle_input_moves: String,
+    puzzle_input_moves: Option<String>,
 }
 
 enum BoxKind {
@@ -13,106 +14,125 @@ enum BoxKind {
     Right,
 }
 
-enum Move {
+#[derive(Clone)]
+pub enum Move {
     Up,
     Down,
     Left,
     Right,
 }
 
-struct Moves {
+#[derive(Clone)]
+pub struct Moves {
     moves: Vec<Move>,
 }
 
 impl Moves {
-    pub fn make(raw_data: &str) -> Result<Moves, &'static str> {
-        let raw_moves: Vec<char> = raw_data
-            .trim()
-            .as_bytes()
-            .iter()
-            .map(|b| *b as char)
-            .filter(|c| *c as char != '\n')
-            .collect();
+    pub fn make(raw_data: &str) -> Result<Moves, PuzzleError> {
+        Moves::make_from_line(raw_data, 1)
+    }
 
+    // `first_line` is the line number of the first line of `raw_data` in
+    // its file.
+    fn make_from_line(raw_data: &str, first_line: usize) -> Result<Moves, PuzzleError> {
         let mut moves = Vec::new();
-        let mut is_ok = true;
-        raw_moves.iter().for_each(|c| match *c {
-            '^' => moves.push(Move::Up),
-            '>' => moves.push(Move::Right),
-            'v' => moves.push(Move::Down),
-            '<' => moves.push(Move::Left),
-            _ => is_ok = false,
-        });
-
-        if !is_ok {
-            return Err("Some moves were not acceptable");
+        for (line, l) in raw_data.lines().enumerate() {
+            for (column, c) in l.chars().enumerate() {
+                match c {
+                    '^' => moves.push(Move::Up),
+                    '>' => moves.push(Move::Right),
+                    'v' => moves.push(Move::Down),
+                    '<' => moves.push(Move::Left),
+                    c if c.is_whitespace() => (),
+                    _ => {
+                        return Err(PuzzleError::at(
+                            first_line + line,
+                            column + 1,
+                            format!("invalid move '{c}'"),
+                        ))
+                    }
+                }
+            }
         }
 
         Ok(Moves { moves })
     }
 }
 
-struct WarehouseMap {
-    positions: Vec<Vec<char>>,
-    simulated_positions: Vec<Vec<char>>,
-    rows: usize,
-    columns: usize,
+#[derive(Clone)]
+pub struct WarehouseMap {
+    positions: Grid<char>,
+    simulated_positions: Grid<char>,
+    #[allow(dead_code)]
     start_position: (usize, usize),
     position: (usize, usize),
 }
 
 impl WarehouseMap {
-    pub fn make(raw_data: &str) -> Result<WarehouseMap, &'static str> {
-        let lines: Vec<String> = raw_data.trim().split("\n").map(|s| s.to_string()).collect();
-        let num_rows = lines.len();
-        if num_rows == 0 {
-            return Err("No row deserialized");
-        }
-
-        let mut positions = Vec::new();
-        let mut is_ok = true;
-        let mut num_columns = 0;
-        for l in lines {
-            let chars: Vec<char> = l.chars().into_iter().collect();
-
-            let length = chars.len();
-            if num_columns == 0 {
-                num_columns = length;
-            } else {
-                if length != num_columns {
-                    is_ok = false;
-                }
-            }
-
-            positions.push(chars);
-        }
-
-        if !is_ok {
-            return Err("Wrong line length for map.");
-        }
-
-        let mut start_position = (0usize, 0usize);
-        for (iy, row) in positions.iter().enumerate() {
-            for (ix, c) in row.iter().enumerate() {
-                if *c == '@' {
-                    start_position = (ix, iy);
-                    break;
-                }
-            }
-        }
+    pub fn make(raw_data: &str) -> Result<WarehouseMap, PuzzleError> {
+        let positions = Grid::build(raw_data)?;
+        let start_position = positions
+            .find(&'@')
+            .ok_or_else(|| PuzzleError::invalid("no robot '@' in map"))?;
 
         let simulated_positions = positions.clone();
 
         Ok(WarehouseMap {
             positions,
             simulated_positions,
-            rows: num_rows,
-            columns: num_columns,
             start_position,
             position: start_position,
         })
     }
 
+    /// Whether boxes span two cells, as `[]`.
+    fn is_wide(&self) -> bool {
+        self.positions.find(&'[').is_some()
+    }
+
+    /// The same warehouse with every cell doubled horizontally.
+    fn widened(&self) -> Result<WarehouseMap, PuzzleError> {
+        let mut raw_data = String::new();
+        for row in self.positions.iter_rows() {
+            for c in row {
+                raw_data.push_str(match c {
+                    '#' => "##",
+                    'O' => "[]",
+                    '@' => "@.",
+                    _ => "..",
+                });
+            }
+            raw_data.push('\n');
+        }
+
+        WarehouseMap::make(&raw_data)
+    }
+
+    /// The same warehouse with every pair of cells merged into one.
+    fn narrowed(&self) -> Result<WarehouseMap, PuzzleError> {
+        let mut raw_data = String::new();
+        for (y, row) in self.positions.iter_rows().enumerate() {
+            for (x, pair) in row.chunks(2).enumerate() {
+                raw_data.push(match pair {
+                    ['#', '#'] => '#',
+                    ['[', ']'] => 'O',
+                    ['@', '.'] => '@',
+                    ['.', '.'] => '.',
+                    _ => {
+                        return Err(PuzzleError::at(
+                            y + 1,
+                            2 * x + 1,
+                            format!("cannot narrow '{}'", pair.iter().collect::<String>()),
+                        ))
+                    }
+                });
+            }
+            raw_data.push('\n');
+        }
+
+        WarehouseMap::make(&raw_data)
+    }
+
     pub fn update_with_move(&mut self, m: &Move) {
         self.do_move(self.position, m);
     }
@@ -121,51 +141,31 @@ impl WarehouseMap {
         self.simulated_positions = self.positions.clone();
 
         self.try_move_large(self.position, m);
-        match self.check_invariants(&self.simulated_positions) {
-            Ok(_) => {
-                println!("Invariants ok");
-                self.positions = self.simulated_positions.clone();
-            }
-            Err(msg) => {
-                println!("{msg}");
-
-                let mut actual = String::new();
-                for r in self.simulated_positions.iter() {
-                    let row: String = r.iter().collect();
-                    actual += &format!("{}\n", row);
-                }
-                println!("Simulated map:\n{actual}");
-            }
+        // a blocked move leaves the simulated map broken, and is dropped
+        if self.check_invariants(&self.simulated_positions).is_ok() {
+            self.positions = self.simulated_positions.clone();
         }
 
         let inv = self.check_invariants(&self.positions);
         if let Err(msg) = inv {
-            let mut actual = String::new();
-            for r in self.positions.iter() {
-                let row: String = r.iter().collect();
-                actual += &format!("{}\n", row);
-            }
-            panic!("Invariants failed on real map. Msg: {msg}\n{actual}");
+            panic!(
+                "Invariants failed on real map. Msg: {msg}\n{}",
+                self.positions
+            );
         }
     }
 
     fn do_move(&mut self, current: (usize, usize), m: &Move) -> bool {
-        assert!(current.0 < self.columns);
-        assert!(current.1 < self.rows);
-
         if self.is_non_movable(current, &self.positions) {
-            println!("Not moving from {current:?} because not movable");
             return false;
         }
 
         let candidate = self.get_pos_from_current_and_move(current, m);
         if let Some(pos) = candidate {
             if self.is_free(pos, &self.positions) || self.do_move(pos, m) {
-                println!("Moving to {pos:?}");
-
-                let cur_object = self.positions[current.1][current.0];
-                self.positions[pos.1][pos.0] = cur_object;
-                self.positions[current.1][current.0] = '.';
+                let cur_object = self.positions[current];
+                self.positions[pos] = cur_object;
+                self.positions[current] = '.';
 
                 if cur_object == '@' {
                     self.position = pos;
@@ -175,16 +175,11 @@ impl WarehouseMap {
             }
         }
 
-        println!("Not moving from {current:?}");
         false
     }
 
     fn try_move_large(&mut self, current: (usize, usize), m: &Move) -> bool {
-        assert!(current.0 < self.columns);
-        assert!(current.1 < self.rows);
-
         if self.is_non_movable(current, &self.simulated_positions) {
-            println!("Not moving from {current:?} because not movable");
             return false;
         }
 
@@ -194,11 +189,9 @@ impl WarehouseMap {
         let candidate = self.get_pos_from_current_and_move(current, m);
         if let Some(pos) = candidate {
             if self.is_free(pos, &self.simulated_positions) || self.try_move_large(pos, m) {
-                println!("Moving to {pos:?}");
-
-                let cur_object = self.simulated_positions[current.1][current.0];
-                self.simulated_positions[pos.1][pos.0] = cur_object;
-                self.simulated_positions[current.1][current.0] = '.';
+                let cur_object = self.simulated_positions[current];
+                self.simulated_positions[pos] = cur_object;
+                self.simulated_positions[current] = '.';
 
                 if cur_object == '@' {
                     self.position = pos;
@@ -209,53 +202,38 @@ impl WarehouseMap {
         }
 
         if success {
-            println!("Successfully moved from position.");
             if let Some(kind) = box_part {
-                println!("Moving second part of a box");
                 match *m {
                     Move::Up | Move::Down => {
                         let other_part_pos = match kind {
                             BoxKind::Left => (current.0 + 1, current.1),
                             BoxKind::Right => (current.0 - 1, current.1),
                         };
-                        println!(
-                            "Attempting to move vertical second part from pos {other_part_pos:?}"
-                        );
 
                         let candidate = self.get_pos_from_current_and_move(other_part_pos, m);
                         if let Some(pos) = candidate {
                             if self.is_free(pos, &self.simulated_positions)
                                 || self.try_move_large(pos, m)
                             {
-                                println!("Moving to {pos:?} second part of a box");
-
-                                let cur_object =
-                                    self.simulated_positions[other_part_pos.1][other_part_pos.0];
-                                self.simulated_positions[pos.1][pos.0] = cur_object;
-                                self.simulated_positions[other_part_pos.1][other_part_pos.0] = '.';
+                                let cur_object = self.simulated_positions[other_part_pos];
+                                self.simulated_positions[pos] = cur_object;
+                                self.simulated_positions[other_part_pos] = '.';
 
                                 return true;
                             }
-                            println!(
-                                "Couldn't move because next is either not free or couldn't move"
-                            );
                             return false;
                         }
-                        println!("Couldn't move because no candidate was available.");
                         return false;
                     }
                     _ => {
-                        println!("Horizontal movement - no need to do anything");
                         return true;
                     }
                 }
             } else {
-                println!("No need to do anything else.");
                 return true;
             }
         }
 
-        println!("Not moving from {current:?}");
         false
     }
 
@@ -264,94 +242,36 @@ impl WarehouseMap {
         current: (usize, usize),
         m: &Move,
     ) -> Option<(usize, usize)> {
-        let current = (
-            i32::try_from(current.0).unwrap(),
-            i32::try_from(current.1).unwrap(),
-        );
-        let next = match m {
-            Move::Up => (current.0, current.1 - 1),
-            Move::Right => (current.0 + 1, current.1),
-            Move::Down => (current.0, current.1 + 1),
-            Move::Left => (current.0 - 1, current.1),
+        let delta = match m {
+            Move::Up => (0, -1),
+            Move::Right => (1, 0),
+            Move::Down => (0, 1),
+            Move::Left => (-1, 0),
         };
 
-        if self.is_inside_map(next) {
-            return Some((
-                usize::try_from(next.0).unwrap(),
-                usize::try_from(next.1).unwrap(),
-            ));
-        }
-
-        None
-    }
-
-    fn is_inside_map(&self, pos: (i32, i32)) -> bool {
-        pos.0 >= 0
-            && pos.1 >= 0
-            && usize::try_from(pos.0).unwrap() < self.columns
-            && usize::try_from(pos.1).unwrap() < self.rows
+        self.positions.offset(current, delta)
     }
 
-    fn is_free(&self, pos: (usize, usize), positions: &Vec<Vec<char>>) -> bool {
-        assert!(pos.0 < self.columns);
-        assert!(pos.1 < self.rows);
-
-        match positions[pos.1][pos.0] {
-            '#' | '@' | 'O' | '[' | ']' => {
-                println!("Position {:?} is not free", pos);
-                false
-            }
-            _ => {
-                println!("Position {:?} is free", pos);
-                true
-            }
-        }
+    fn is_free(&self, pos: (usize, usize), positions: &Grid<char>) -> bool {
+        !matches!(positions[pos], '#' | '@' | 'O' | '[' | ']')
     }
 
-    fn is_non_movable(&self, pos: (usize, usize), positions: &Vec<Vec<char>>) -> bool {
-        assert!(pos.0 < self.columns);
-        assert!(pos.1 < self.rows);
-
-        match positions[pos.1][pos.0] {
-            '#' => {
-                println!("Position {:?} is not movable", pos);
-                true
-            }
-            _ => {
-                println!("Position {:?} is either free or movable", pos);
-                false
-            }
-        }
+    fn is_non_movable(&self, pos: (usize, usize), positions: &Grid<char>) -> bool {
+        positions[pos] == '#'
     }
 
-    fn try_get_box(&self, pos: (usize, usize), positions: &Vec<Vec<char>>) -> Option<BoxKind> {
-        assert!(pos.0 < self.columns);
-        assert!(pos.1 < self.rows);
-
-        match positions[pos.1][pos.0] {
-            '[' => {
-                println!("Position {:?} contains left-part of a box", pos);
-                return Some(BoxKind::Left);
-            }
-            ']' => {
-                println!("Position {:?} contains right-part of a box", pos);
-                return Some(BoxKind::Right);
-            }
-            _ => {
-                println!("Position {:?} is either free or simply movable", pos);
-                return None;
-            }
+    fn try_get_box(&self, pos: (usize, usize), positions: &Grid<char>) -> Option<BoxKind> {
+        match positions[pos] {
+            '[' => Some(BoxKind::Left),
+            ']' => Some(BoxKind::Right),
+            _ => None,
         }
     }
 
     pub fn get_boxes_coordinates_sum(&self) -> u64 {
         let mut total = 0u64;
-        for (iy, row) in self.positions.iter().enumerate() {
-            for (ix, c) in row.iter().enumerate() {
-                if *c == 'O' {
-                    total += u64::try_from(ix).unwrap() + 100 * u64::try_from(iy).unwrap();
-                }
-            }
+        for (ix, iy) in self.positions.find_all(&'O') {
+            total += u64::try_from(ix).unwrap() + 100 * u64::try_from(iy).unwrap();
         }
 
         total
@@ -359,69 +279,65 @@ impl WarehouseMap {
 
     pub fn get_boxes_coordinates_large_sum(&self) -> u64 {
         let mut total = 0u64;
-        for (iy, row) in self.positions.iter().enumerate() {
-            for (ix, c) in row.iter().enumerate() {
-                if *c == '[' {
-                    total += u64::try_from(ix).unwrap() + 100 * u64::try_from(iy).unwrap();
-                }
-            }
+        for (ix, iy) in self.positions.find_all(&'[') {
+            total += u64::try_from(ix).unwrap() + 100 * u64::try_from(iy).unwrap();
         }
 
         total
     }
 
-    fn check_invariants(&self, positions: &Vec<Vec<char>>) -> Result<(), String> {
+    fn check_invariants(&self, positions: &Grid<char>) -> Result<(), String> {
         // main invariant: map should have aligned boxes parts
-        for (iy, row) in positions.iter().enumerate() {
-            for (ix, c) in row.iter().enumerate() {
-                match *c {
-                    '[' => {
-                        if positions[iy][ix + 1] != ']' {
-                            return Err(format!("Err 1: violated invariant at ({}, {})", ix, iy));
-                        }
+        for ((ix, iy), c) in positions.iter() {
+            match *c {
+                '[' if positions[(ix + 1, iy)] != ']' => {
+                    return Err(format!("Err 1: violated invariant at ({}, {})", ix, iy));
+                }
+                ']' => {
+                    if ix <= 2 {
+                        return Err(format!("Err 2: violated invariant at ({}, {})", ix, iy));
                     }
-                    ']' => {
-                        if ix <= 2 {
-                            return Err(format!("Err 2: violated invariant at ({}, {})", ix, iy));
-                        }
 
-                        if positions[iy][ix - 1] != '[' {
-                            return Err(format!("Err 3: violated invariant at ({}, {})", ix, iy));
-                        }
+                    if positions[(ix - 1, iy)] != '[' {
+                        return Err(format!("Err 3: violated invariant at ({}, {})", ix, iy));
                     }
-                    _ => (),
                 }
+                _ => (),
             }
         }
 
-        return Ok(());
+        Ok(())
     }
 
+    #[cfg(test)]
     pub fn rows(&self) -> usize {
-        self.rows
+        self.positions.rows()
     }
 
+    #[cfg(test)]
     pub fn columns(&self) -> usize {
-        self.columns
+        self.positions.columns()
     }
 
+    #[cfg(test)]
     pub fn start_position(&self) -> (usize, usize) {
         self.start_position
     }
 
+    #[cfg(test)]
     pub fn position(&self) -> (usize, usize) {
         self.position
     }
 }
 
 impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
-        if args.len() < 3 {
-            return Err("Not enough arguments");
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
+        if args.len() < 2 {
+            return Err(PuzzleError::usage("Not enough arguments"));
         }
 
         let puzzle_input_map = args[1].clone();
-        let puzzle_input_moves = args[2].clone();
+        let puzzle_input_moves = args.get(2).cloned();
 
         Ok(Config {
             puzzle_input_map,
@@ -430,20 +346,103 @@ impl Config {
     }
 }
 
-pub fn run(config: Config) -> Result<(u64), Box<dyn Error>> {
-    let map_content = fs::read_to_string(config.puzzle_input_map)?;
-    let moves_content = fs::read_to_string(config.puzzle_input_moves)?;
+/// Parses the puzzle input as downloaded: the map, a blank line, then the
+/// moves.
+fn parse_puzzle_input(raw_data: &str) -> Result<(WarehouseMap, Moves), PuzzleError> {
+    let lines: Vec<&str> = raw_data.lines().collect();
+    let first_row = lines.iter().position(|l| !l.trim().is_empty()).unwrap_or(0);
+    let separator = lines[first_row..]
+        .iter()
+        .position(|l| l.trim().is_empty())
+        .map_or(lines.len(), |index| first_row + index);
 
-    let mut map = WarehouseMap::make(&map_content).unwrap();
-    let movements = Moves::make(&moves_content).unwrap();
+    let map = WarehouseMap::make(&lines[..separator].join("\n"))?;
+    let moves = match lines.get(separator + 1..) {
+        Some(move_lines) => Moves::make_from_line(&move_lines.join("\n"), separator + 2)?,
+        None => Moves { moves: Vec::new() },
+    };
 
-    movements
-        .moves
-        .iter()
-        .for_each(|m| map.update_with_move_large(m));
+    Ok((map, moves))
+}
+
+/// The warehouse in both its narrow and wide layouts, whichever of the two
+/// the input used, along with the robot moves.
+pub struct Warehouse {
+    narrow: WarehouseMap,
+    wide: WarehouseMap,
+    moves: Moves,
+}
+
+impl Warehouse {
+    fn make(map: WarehouseMap, moves: Moves) -> Result<Warehouse, PuzzleError> {
+        let (narrow, wide) = if map.is_wide() {
+            (map.narrowed()?, map)
+        } else {
+            let wide = map.widened()?;
+            (map, wide)
+        };
+
+        Ok(Warehouse {
+            narrow,
+            wide,
+            moves,
+        })
+    }
+}
+
+pub struct Day15;
+
+impl Solution for Day15 {
+    type Input = Warehouse;
+    type Answer = u64;
+
+    fn parse(raw_input: &str) -> Result<Warehouse, PuzzleError> {
+        let (map, moves) = parse_puzzle_input(raw_input)?;
+        Warehouse::make(map, moves)
+    }
 
-    let boxes_coordinates_sum = map.get_boxes_coordinates_large_sum();
-    Ok((boxes_coordinates_sum))
+    fn part1(input: &Warehouse) -> u64 {
+        let mut map = input.narrow.clone();
+        input
+            .moves
+            .moves
+            .iter()
+            .for_each(|m| map.update_with_move(m));
+        map.get_boxes_coordinates_sum()
+    }
+
+    fn part2(input: &Warehouse) -> u64 {
+        let mut map = input.wide.clone();
+        input
+            .moves
+            .moves
+            .iter()
+            .for_each(|m| map.update_with_move_large(m));
+        map.get_boxes_coordinates_large_sum()
+    }
+}
+
+pub fn run(config: Config) -> Result<(u64, u64), PuzzleError> {
+    let map_content = puzzle::read_input(&config.puzzle_input_map)?;
+
+    let (map, movements) = match &config.puzzle_input_moves {
+        Some(puzzle_input_moves) => {
+            let moves_content = puzzle::read_input(puzzle_input_moves)?;
+            let map = WarehouseMap::make(&map_content)
+                .map_err(|e| e.in_file(&config.puzzle_input_map))?;
+            let movements =
+                Moves::make(&moves_content).map_err(|e| e.in_file(puzzle_input_moves))?;
+            (map, movements)
+        }
+        None => {
+            parse_puzzle_input(&map_content).map_err(|e| e.in_file(&config.puzzle_input_map))?
+        }
+    };
+
+    let warehouse =
+        Warehouse::make(map, movements).map_err(|e| e.in_file(&config.puzzle_input_map))?;
+
+    Ok((Day15::part1(&warehouse), Day15::part2(&warehouse)))
 }
 
 // Note on printing during tests:
@@ -452,8 +451,6 @@ pub fn run(config: Config) -> Result<(u64), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use io::BufReader;
-
     use super::*;
 
     #[test]
@@ -465,6 +462,72 @@ mod tests {
         assert_eq!(m.moves.len(), 15);
     }
 
+    #[test]
+    fn invalid_move_test() {
+        let data = "\
+<^^>>
+>vx<v";
+
+        let error = Moves::make(data).err().unwrap();
+        assert_eq!(error.to_string(), "line 2, column 3: invalid move 'x'");
+    }
+
+    #[test]
+    fn single_file_input_test() {
+        let data = "\
+#####
+#@.O#
+#####
+
+>>
+<";
+
+        let (map, movements) = parse_puzzle_input(data).unwrap();
+        assert_eq!(map.rows(), 3);
+        assert_eq!(map.start_position(), (1, 1));
+        assert_eq!(movements.moves.len(), 3);
+
+        let error = parse_puzzle_input(&format!("{data}x")).err().unwrap();
+        assert_eq!(error.to_string(), "line 6, column 2: invalid move 'x'");
+    }
+
+    #[test]
+    fn solution_sample_test() {
+        let data = "\
+##########
+#..O..O.O#
+#......O.#
+#.OO..O.O#
+#..O@..O.#
+#O#..O...#
+#O..O..O.#
+#.OO.O.OO#
+#....O...#
+##########
+
+<vv>^<v^>v>^vv^v>v<>v^v<v<^vv<<<^><<><>>v<vvv<>^v^>^<<<><<v<<<v^vv^v>^
+vvv<<^>^v^^><<>>><>^<<><^vv^^<>vvv<>><^^v>^>vv<>v<<<<v<^v>^<^^>>>^<v<v
+><>vv>v^v^<>><>>>><^^>vv>v<^^^>>v^v^<^^>v^^>v^<^v>v<>>v^v^<v>v^^<^^vv<
+<<v<^>>^^^^>>>v^<>vvv^><v<<<>^^^vv^<vvv>^>v<^^^^v<>^>vvvv><>>v^<<^^^^^
+^><^><>>><>^^<<^^v>>><^<v>^<vv>>v>>>^v><>^v><<<<v>>v<v<v>vvv>^<><<>^><
+^>><>^v<><^vvv<^^<><v<<<<<><^v<<<><<<^^<v<^^^><^>>^<v^><<<^>>^v<v^v<v^
+>^>>^v>vv>^<<^v<>><<><<v<<v><>v<^vv<<<>^^v^>^^>>><<^v>>v^v><^^>>^<>vv^
+<><^^>^^^<><vvvvv^v<v<<>^v<v>v<<^><<><<><<<^^<<<^<<>><<><^^^>^^<>^>v<>
+^^>vv<^v^v<vv>^<><v<^v>^^^>>>^^vvv^>vvv<>>>^<^>>>>>^<<^v>^vvv<>^<><<v>
+v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
+
+        assert_eq!(Day15::solve(data).unwrap(), (10092, 9021));
+
+        // the same warehouse, already widened
+        let warehouse = Day15::parse(data).unwrap();
+        let wide = format!(
+            "{}\n\n{}",
+            warehouse.wide.positions,
+            data.split("\n\n").nth(1).unwrap()
+        );
+        assert_eq!(Day15::solve(&wide).unwrap(), (10092, 9021));
+    }
+
     #[test]
     fn basic_map_creation_test() {
         let data = "\
@@ -514,11 +577,7 @@ mod tests {
 #...O..#
 ########";
 
-        let mut actual = String::new();
-        for r in map.positions.iter() {
-            let row: String = r.iter().collect();
-            actual += &format!("{}\n", row);
-        }
+        let actual = map.positions.to_string();
         assert_eq!(expected, actual.trim());
         assert_eq!(map.get_boxes_coordinates_sum(), 2028);
     }
@@ -569,11 +628,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##......[][]..[]..##
 ####################";
 
-        let mut actual = String::new();
-        for r in map.positions.iter() {
-            let row: String = r.iter().collect();
-            actual += &format!("{}\n", row);
-        }
+        let actual = map.positions.to_string();
         println!("Actual map:\n{actual}");
 
         assert_eq!(expected, actual.trim());
@@ -602,11 +657,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
             println!("----------- Move start -----------");
             map.update_with_move_large(m);
 
-            let mut actual = String::new();
-            for r in map.positions.iter() {
-                let row: String = r.iter().collect();
-                actual += &format!("{}\n", row);
-            }
+            let actual = map.positions.to_string();
             println!("Current map:\n{actual}");
         });
 
@@ -619,11 +670,7 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
 ##..........##
 ##############";
 
-        let mut actual = String::new();
-        for r in map.positions.iter() {
-            let row: String = r.iter().collect();
-            actual += &format!("{}\n", row);
-        }
+        let actual = map.positions.to_string();
         println!("Actual map:\n{actual}");
 
         assert_eq!(expected, actual.trim());
@@ -1,12 +1,33 @@
-use std::{env, error};
+use puzzle::output::Output;
+use std::env;
+use std::time::Instant;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    println!("Hello, day 15!");
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let output = Output::from_args(&mut args);
+    let input = args.get(1).cloned();
 
-    let args: Vec<String> = env::args().collect();
-    let config = day15::Config::build(&args)?;
-    let (sum) = day15::run(config)?;
+    if output.is_text() {
+        println!("Hello, day 15!");
+    }
 
-    println!("Total sum:         {sum}");
-    Ok(())
+    let config = day15::Config::build(&args)
+        .unwrap_or_else(|err| output.fail(15, input.as_deref(), "Problem parsing arguments", &err));
+
+    let start = Instant::now();
+    let (narrow_sum, sum) = day15::run(config)
+        .unwrap_or_else(|err| output.fail(15, input.as_deref(), "Application error", &err));
+    let elapsed = start.elapsed();
+
+    if output.is_text() {
+        println!("Narrow sum:        {narrow_sum}");
+        println!("Total sum:         {sum}");
+    } else {
+        output.answers(
+            15,
+            input.as_deref().unwrap_or_default(),
+            &[&narrow_sum, &sum],
+            elapsed,
+        );
+    }
 }
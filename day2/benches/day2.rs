@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day2::Day2;
+use puzzle::bench;
+
+fn day2(c: &mut Criterion) {
+    let raw_input = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/puzzle-input.txt"
+    ));
+    bench::phases::<Day2>(c, "day2", &raw_input);
+}
+
+criterion_group!(benches, day2);
+criterion_main!(benches);
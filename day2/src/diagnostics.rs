@@ -0,0 +1,252 @@
+use crate::{removals_within, step, Rules, Trend};
+use serde::Serialize;
+use std::fmt;
+
+/// Outcome of the safety check of a report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Verdict {
+    Safe,
+    /// Safe once the dampener drops a level.
+    Dampened,
+    Unsafe,
+}
+
+/// Why a pair of adjacent levels breaks the rules.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Reason {
+    /// The levels go the wrong way, or turn too many times.
+    Oscillation,
+    /// The levels change by too little or too much.
+    Step,
+}
+
+impl fmt::Display for Verdict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Verdict::Safe => write!(f, "safe"),
+            Verdict::Dampened => write!(f, "dampened"),
+            Verdict::Unsafe => write!(f, "unsafe"),
+        }
+    }
+}
+
+impl fmt::Display for Reason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Reason::Oscillation => write!(f, "oscillation"),
+            Reason::Step => write!(f, "step"),
+        }
+    }
+}
+
+/// Verdict of a report along with what explains it. Levels are referred to
+/// by their zero-based index in the report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Diagnostic {
+    /// One-based line of the report in the input.
+    pub line: usize,
+    pub verdict: Verdict,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<Reason>,
+    /// The first pair of adjacent levels breaking the rules.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pair: Option<(usize, usize)>,
+    /// The level whose removal makes a dampened report safe.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub removal: Option<usize>,
+    pub levels: Vec<i32>,
+}
+
+// The first pair of adjacent levels breaking the rules, if any
+fn first_violation(values: &[i32], rules: &Rules) -> Option<(Reason, (usize, usize))> {
+    let mut trend = Trend::Flat;
+    let mut changes = 0;
+    for (index, pair) in values.windows(2).enumerate() {
+        let difference = step(pair);
+        if !rules.allows_step(difference) {
+            return Some((Reason::Step, (index, index + 1)));
+        }
+
+        match trend.follow(Trend::of(difference), changes, rules) {
+            Some((next_trend, next_changes)) => (trend, changes) = (next_trend, next_changes),
+            None => return Some((Reason::Oscillation, (index, index + 1))),
+        }
+    }
+
+    None
+}
+
+impl Diagnostic {
+    pub fn of(line: usize, values: &[i32], rules: &Rules) -> Diagnostic {
+        let violation = first_violation(values, rules);
+        let (verdict, removal) = match violation {
+            None => (Verdict::Safe, None),
+            Some(_) => match removals_within(values, 1, rules) {
+                Some(removals) => (Verdict::Dampened, removals.first().copied()),
+                None => (Verdict::Unsafe, None),
+            },
+        };
+
+        Diagnostic {
+            line,
+            verdict,
+            reason: violation.map(|(reason, _)| reason),
+            pair: violation.map(|(_, pair)| pair),
+            removal,
+            levels: values.to_vec(),
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("diagnostics are plain data")
+    }
+
+    // fields as printed in tables and CSV
+    fn fields(&self) -> [String; 6] {
+        let levels: Vec<String> = self.levels.iter().map(|l| l.to_string()).collect();
+        [
+            self.line.to_string(),
+            self.verdict.to_string(),
+            self.reason.map(|r| r.to_string()).unwrap_or_default(),
+            self.pair
+                .map(|(first, second)| format!("{first}-{second}"))
+                .unwrap_or_default(),
+            self.removal.map(|r| r.to_string()).unwrap_or_default(),
+            levels.join(" "),
+        ]
+    }
+}
+
+const HEADER: [&str; 6] = ["line", "verdict", "reason", "pair", "removal", "levels"];
+
+/// Diagnostics as an aligned table, one report per row.
+pub fn table(diagnostics: &[Diagnostic]) -> String {
+    let rows: Vec<[String; 6]> = diagnostics.iter().map(|d| d.fields()).collect();
+    let mut widths = HEADER.map(str::len);
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.len());
+        }
+    }
+
+    let mut table = String::new();
+    let mut push_row = |fields: [&str; 6]| {
+        let line: Vec<String> = fields
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (field, width))| match column {
+                // numbers are right-aligned
+                0 | 4 => format!("{field:>width$}"),
+                _ => format!("{field:<width$}"),
+            })
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    };
+
+    push_row(HEADER);
+    for row in &rows {
+        push_row(row.each_ref().map(String::as_str));
+    }
+
+    table
+}
+
+/// Diagnostics as CSV with a header row.
+pub fn to_csv(diagnostics: &[Diagnostic]) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(HEADER).expect("writing to memory");
+    for diagnostic in diagnostics {
+        writer
+            .write_record(diagnostic.fields())
+            .expect("writing to memory");
+    }
+
+    let bytes = writer.into_inner().expect("writing to memory");
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_diagnostics() -> Vec<Diagnostic> {
+        let reports: [&[i32]; 6] = [
+            &[7, 6, 4, 2, 1],
+            &[1, 2, 7, 8, 9],
+            &[9, 7, 6, 2, 1],
+            &[1, 3, 2, 4, 5],
+            &[8, 6, 4, 4, 1],
+            &[1, 3, 6, 7, 9],
+        ];
+
+        let rules = Rules::default();
+        reports
+            .iter()
+            .enumerate()
+            .map(|(index, values)| Diagnostic::of(index + 1, values, &rules))
+            .collect()
+    }
+
+    #[test]
+    fn diagnose_sample_input() {
+        let diagnostics = sample_diagnostics();
+        let summary: Vec<_> = diagnostics
+            .iter()
+            .map(|d| (d.verdict, d.reason, d.pair, d.removal))
+            .collect();
+
+        assert_eq!(
+            summary,
+            [
+                (Verdict::Safe, None, None, None),
+                (Verdict::Unsafe, Some(Reason::Step), Some((1, 2)), None),
+                (Verdict::Unsafe, Some(Reason::Step), Some((2, 3)), None),
+                (
+                    Verdict::Dampened,
+                    Some(Reason::Oscillation),
+                    Some((1, 2)),
+                    Some(1)
+                ),
+                (Verdict::Dampened, Some(Reason::Step), Some((2, 3)), Some(3)),
+                (Verdict::Safe, None, None, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn diagnostics_output() {
+        let diagnostics = sample_diagnostics();
+        assert_eq!(
+            diagnostics[3].to_json(),
+            r#"{"line":4,"verdict":"dampened","reason":"oscillation","pair":[1,2],"removal":1,"levels":[1,3,2,4,5]}"#
+        );
+        assert_eq!(
+            diagnostics[0].to_json(),
+            r#"{"line":1,"verdict":"safe","levels":[7,6,4,2,1]}"#
+        );
+
+        let table = table(&diagnostics[..2]);
+        assert_eq!(
+            table,
+            "\
+line  verdict  reason  pair  removal  levels
+   1  safe                            7 6 4 2 1
+   2  unsafe   step    1-2            1 2 7 8 9
+"
+        );
+
+        let csv = to_csv(&diagnostics[3..4]);
+        assert_eq!(
+            csv,
+            "line,verdict,reason,pair,removal,levels\n4,dampened,oscillation,1-2,1,1 3 2 4 5\n"
+        );
+    }
+}
@@ -1,112 +1,412 @@
-use csv::Reader;
-use serde::de::DeserializeOwned;
-use std::io::{self, Read};
-use std::{error::Error, fs, fs::File, process};
+mod diagnostics;
+mod repair;
+mod rules;
+
+pub use diagnostics::{Diagnostic, Reason, Verdict};
+pub use repair::{Change, Repair, MAX_REPAIR_LEVELS};
+pub use rules::{Direction, Rules};
+
+use puzzle::{PuzzleError, Solution};
+use std::cmp::Reverse;
+use std::ops::Range;
+
+/// What to compute from the reports.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mode {
+    /// The answers of the puzzle.
+    Counts,
+    /// The verdict of every report, from `--report`.
+    Report,
+    /// The fewest level values to change in every report, from `--repair`.
+    Repair,
+}
 
 #[derive(Debug)]
 pub struct Config {
     puzzle_input: String,
+    rules: Rules,
+    mode: Mode,
 }
 
 impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
         if args.len() < 2 {
-            return Err("Not enough arguments");
+            return Err(PuzzleError::usage("Not enough arguments"));
         }
 
         let puzzle_input = args[1].clone();
 
-        Ok(Config { puzzle_input })
+        let is_mode = |arg: &&String| *arg == "--report" || *arg == "--repair";
+        let modes: Vec<&String> = args[2..].iter().filter(is_mode).collect();
+        let mode = match modes.as_slice() {
+            [] => Mode::Counts,
+            [mode] if *mode == "--report" => Mode::Report,
+            [_] => Mode::Repair,
+            _ => return Err(PuzzleError::usage("Use only one of --report and --repair")),
+        };
+
+        let options: Vec<String> = args[2..]
+            .iter()
+            .filter(|arg| !is_mode(arg))
+            .cloned()
+            .collect();
+        let rules = Rules::from_args(&options)?;
+
+        Ok(Config {
+            puzzle_input,
+            rules,
+            mode,
+        })
+    }
+
+    pub fn mode(&self) -> Mode {
+        self.mode
     }
 }
 
-fn get_lines(raw_input: &str) -> Vec<&str> {
-    let chunks: Vec<&str> = raw_input.trim().split("\n").collect();
-    chunks
+// Non-blank lines along with their one-based line number
+fn get_lines(raw_input: &str) -> Vec<(usize, &str)> {
+    raw_input
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(index, line)| (index + 1, line))
+        .collect()
 }
 
-fn get_values_from_line(line: &str) -> Vec<i32> {
-    let chunks = line.trim().split_whitespace();
-    chunks
+fn get_reports(raw_input: &str) -> Result<Vec<(usize, Vec<i32>)>, PuzzleError> {
+    get_lines(raw_input)
         .into_iter()
-        .map(|x| x.parse::<i32>().unwrap())
+        .map(|(line_number, line)| {
+            get_values_from_line(line)
+                .map(|values| (line_number, values))
+                .map_err(|e| e.on_line(line_number))
+        })
         .collect()
 }
 
-fn is_safe(values: &Vec<i32>) -> bool {
-    are_not_oscillating(values) && are_all_gradual_changing(values)
+fn get_values_from_line(line: &str) -> Result<Vec<i32>, PuzzleError> {
+    line.split_whitespace()
+        .map(|x| puzzle::parse_number(line, x))
+        .collect()
 }
 
-fn is_safe_loose(values: &Vec<i32>) -> bool {
-    if is_safe(values) {
-        return true;
-    }
+fn is_safe(values: &[i32], rules: &Rules) -> bool {
+    are_not_oscillating(values, rules) && are_all_gradual_changing(values, rules)
+}
 
-    let mut is_loosely_valid = false;
+fn is_safe_loose(values: &[i32], rules: &Rules) -> bool {
+    removals_within(values, 1, rules).is_some()
+}
 
-    for ix in 0..values.len() {
-        let mut v = values.clone();
+/// Way the levels go: for a step, or for a report so far, where `Flat`
+/// means that no direction was taken yet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Trend {
+    Flat,
+    Up,
+    Down,
+}
 
-        v.remove(ix);
+impl Trend {
+    const ALL: [Trend; 3] = [Trend::Flat, Trend::Up, Trend::Down];
 
-        let safe = is_safe(&v);
-        is_loosely_valid |= safe;
+    fn of(difference: i64) -> Trend {
+        match difference.signum() {
+            1 => Trend::Up,
+            -1 => Trend::Down,
+            _ => Trend::Flat,
+        }
     }
 
-    is_loosely_valid
+    // The trend and the number of direction changes after taking `step`,
+    // if the rules allow it
+    fn follow(self, step: Trend, changes: usize, rules: &Rules) -> Option<(Trend, usize)> {
+        match (self, step) {
+            (_, Trend::Flat) => {
+                (rules.direction == Direction::NonStrict).then_some((self, changes))
+            }
+            (Trend::Flat, step) => match (rules.direction, step) {
+                (Direction::Increasing, Trend::Down) | (Direction::Decreasing, Trend::Up) => None,
+                _ => Some((step, changes)),
+            },
+            (trend, step) if trend == step => Some((step, changes)),
+            (_, step) => (changes < rules.max_direction_changes).then_some((step, changes + 1)),
+        }
+    }
 }
 
-fn are_not_oscillating(values: &[i32]) -> bool {
-    values.iter().is_sorted() || values.iter().rev().is_sorted()
+fn step(pair: &[i32]) -> i64 {
+    i64::from(pair[1]) - i64::from(pair[0])
 }
 
-fn are_all_gradual_changing(values: &[i32]) -> bool {
-    let mut all_gradual_changing = true;
-    let _ = values
+fn are_not_oscillating(values: &[i32], rules: &Rules) -> bool {
+    values
         .windows(2)
-        .map(|w| {
-            let diff = (w[0] - w[1]).abs();
-            if diff < 1 || diff > 3 {
-                all_gradual_changing = false;
-            }
+        .try_fold((Trend::Flat, 0), |(trend, changes), pair| {
+            trend.follow(Trend::of(step(pair)), changes, rules)
         })
-        .collect::<Vec<_>>();
-    all_gradual_changing
+        .is_some()
 }
 
-fn deserialize<T, R>(reader: R) -> Result<Vec<T>, Box<dyn std::error::Error>>
-where
-    T: std::fmt::Debug + DeserializeOwned,
-    R: Read,
-{
-    let mut rdr = Reader::from_reader(reader);
-    let mut structs: Vec<T> = Vec::new();
-    for result in rdr.deserialize() {
-        let record: T = result?;
-        structs.push(record);
-    }
+fn are_all_gradual_changing(values: &[i32], rules: &Rules) -> bool {
+    values.windows(2).all(|pair| rules.allows_step(step(pair)))
+}
 
-    Ok(structs)
+// Longest chain of levels ending at some index in some state
+#[derive(Debug, Clone, Copy)]
+struct Link {
+    length: usize,
+    // index and state the chain comes from
+    previous: Option<(usize, usize)>,
 }
 
-pub fn run(config: Config) -> Result<(i32, i32), Box<dyn Error>> {
-    let content = fs::read_to_string(config.puzzle_input)?;
+// Length of a chain, then the position of its last value and its last
+// index, reversed so that the greatest is the one to keep
+type Rank = (usize, Reverse<usize>, Reverse<usize>);
+
+// Longest chain, as its length and the index ending it, per range of
+// level values: a bottom-up segment tree over the distinct values. Of
+// chains of the same length, the one ending at the lowest value, then the
+// one ending first, is kept.
+struct Chains {
+    size: usize,
+    nodes: Vec<Option<Rank>>,
+}
+
+impl Chains {
+    fn new(size: usize) -> Chains {
+        Chains {
+            size,
+            nodes: vec![None; 2 * size],
+        }
+    }
+
+    fn insert(&mut self, position: usize, (length, index): (usize, usize)) {
+        let chain = (length, Reverse(position), Reverse(index));
+        let mut node = position + self.size;
+        while node > 0 {
+            self.nodes[node] = self.nodes[node].max(Some(chain));
+            node /= 2;
+        }
+    }
+
+    fn longest(&self, positions: Range<usize>) -> Option<(usize, usize)> {
+        let mut low = positions.start + self.size;
+        let mut high = positions.end + self.size;
+        let mut longest = None;
+        while low < high {
+            if low % 2 == 1 {
+                longest = longest.max(self.nodes[low]);
+                low += 1;
+            }
+            if high % 2 == 1 {
+                high -= 1;
+                longest = longest.max(self.nodes[high]);
+            }
+            low /= 2;
+            high /= 2;
+        }
+        longest.map(|(length, _, Reverse(index))| (length, index))
+    }
+}
 
-    let lines = get_lines(&content);
-    let mut num_safe = 0;
-    let mut num_loosely_safe = 0;
-    for line in lines {
-        let values = get_values_from_line(line);
-        if is_safe(&values) {
-            num_safe += 1;
+/// The fewest levels to drop, by index, for `values` to become safe, or
+/// `None` if that takes more than `k` of them.
+///
+/// This finds the longest subsequence of safe levels. A state is a trend
+/// along with the direction changes used to reach it; the longest chain
+/// ending at a level in a state extends the longest chain ending, earlier,
+/// at one of the levels the rules let it follow. Those are found with a
+/// range query over the level values per state, so that every level costs
+/// O(log n) whatever the step bounds.
+pub fn removals_within(values: &[i32], k: usize, rules: &Rules) -> Option<Vec<usize>> {
+    let changes = rules.max_direction_changes + 1;
+    let state_of = |trend: Trend, used: usize| trend as usize * changes + used;
+    let states = Trend::ALL.len() * changes;
+
+    let min_step = i64::from(rules.min_step.max(1));
+    let max_step = i64::from(rules.max_step);
+    let flat_allowed = rules.allows_step(0);
+
+    let mut levels: Vec<i64> = values.iter().map(|&value| i64::from(value)).collect();
+    levels.sort_unstable();
+    levels.dedup();
+    // positions of the distinct levels from `low` to `high`
+    let between = |low: i64, high: i64| {
+        levels.partition_point(|&level| level < low)..levels.partition_point(|&level| level <= high)
+    };
+
+    let mut links: Vec<Vec<Option<Link>>> = Vec::with_capacity(values.len());
+    let mut chains: Vec<Chains> = (0..states).map(|_| Chains::new(levels.len())).collect();
+
+    for (index, &value) in values.iter().enumerate() {
+        let value = i64::from(value);
+        let position = levels.partition_point(|&level| level < value);
+        // the levels a step of each trend comes from
+        let origins = [
+            (Trend::Up, between(value - max_step, value - min_step)),
+            (Trend::Down, between(value + min_step, value + max_step)),
+            (Trend::Flat, position..position + usize::from(flat_allowed)),
+        ];
+
+        let mut current: Vec<Option<Link>> = vec![None; states];
+        current[state_of(Trend::Flat, 0)] = Some(Link {
+            length: 1,
+            previous: None,
+        });
+
+        for trend in Trend::ALL {
+            for used in 0..changes {
+                let state = state_of(trend, used);
+                for (step, origin) in &origins {
+                    let Some((next_trend, next_used)) = trend.follow(*step, used, rules) else {
+                        continue;
+                    };
+                    let Some((length, previous)) = chains[state].longest(origin.clone()) else {
+                        continue;
+                    };
+
+                    let next = &mut current[state_of(next_trend, next_used)];
+                    if next.is_none_or(|link| link.length < length + 1) {
+                        *next = Some(Link {
+                            length: length + 1,
+                            previous: Some((previous, state)),
+                        });
+                    }
+                }
+            }
         }
 
-        if is_safe_loose(&values) {
-            num_loosely_safe += 1;
+        for (state, link) in current.iter().enumerate() {
+            if let Some(link) = link {
+                chains[state].insert(position, (link.length, index));
+            }
         }
+        links.push(current);
+    }
+
+    let mut end = (0..values.len())
+        .flat_map(|index| (0..states).map(move |state| (index, state)))
+        .filter(|&(index, state)| links[index][state].is_some())
+        .max_by_key(|&(index, state)| links[index][state].map(|link| link.length));
+    let mut kept = Vec::new();
+    while let Some((index, state)) = end {
+        kept.push(index);
+        end = links[index][state].and_then(|link| link.previous);
     }
+    kept.reverse();
 
-    Ok((num_safe, num_loosely_safe))
+    let mut kept = kept.into_iter().peekable();
+    let removals: Vec<usize> = (0..values.len())
+        .filter(|&index| kept.next_if_eq(&index).is_none())
+        .collect();
+
+    if removals.len() <= k {
+        Some(removals)
+    } else {
+        None
+    }
+}
+
+fn count_safe(reports: &[Vec<i32>], tolerance: usize, rules: &Rules) -> i32 {
+    reports
+        .iter()
+        .filter(|values| removals_within(values, tolerance, rules).is_some())
+        .count() as i32
+}
+
+/// One report per line of the puzzle input.
+pub struct Day2;
+
+impl Solution for Day2 {
+    type Input = Vec<Vec<i32>>;
+    type Answer = i32;
+
+    fn parse(raw_input: &str) -> Result<Vec<Vec<i32>>, PuzzleError> {
+        Ok(get_reports(raw_input)?
+            .into_iter()
+            .map(|(_, values)| values)
+            .collect())
+    }
+
+    fn part1(input: &Vec<Vec<i32>>) -> i32 {
+        let rules = Rules::default();
+        input
+            .iter()
+            .filter(|values| is_safe(values, &rules))
+            .count() as i32
+    }
+
+    fn part2(input: &Vec<Vec<i32>>) -> i32 {
+        let rules = Rules::default();
+        input
+            .iter()
+            .filter(|values| is_safe_loose(values, &rules))
+            .count() as i32
+    }
+}
+
+/// Counts the safe reports, then the ones that are safe once the dampener
+/// drops a level, under the rules of `config`.
+pub fn run(config: Config) -> Result<(i32, i32), PuzzleError> {
+    let content = puzzle::read_input(&config.puzzle_input)?;
+    let reports = Day2::parse(&content).map_err(|e| e.in_file(&config.puzzle_input))?;
+
+    Ok((
+        count_safe(&reports, 0, &config.rules),
+        count_safe(&reports, 1, &config.rules),
+    ))
+}
+
+/// The verdict of every report, with the reason of unsafe ones.
+pub fn run_report(config: Config) -> Result<Vec<Diagnostic>, PuzzleError> {
+    let content = puzzle::read_input(&config.puzzle_input)?;
+    let reports = get_reports(&content).map_err(|e| e.in_file(&config.puzzle_input))?;
+
+    Ok(reports
+        .iter()
+        .map(|(line, values)| Diagnostic::of(*line, values, &config.rules))
+        .collect())
+}
+
+/// Diagnostics as an aligned table.
+pub fn report_table(diagnostics: &[Diagnostic]) -> String {
+    diagnostics::table(diagnostics)
+}
+
+/// Diagnostics as CSV.
+pub fn report_csv(diagnostics: &[Diagnostic]) -> String {
+    diagnostics::to_csv(diagnostics)
+}
+
+/// The fewest level values to change in every report for it to be safe,
+/// with the values to use instead. Reports of more than
+/// [`MAX_REPAIR_LEVELS`] levels are an error.
+pub fn run_repair(config: Config) -> Result<Vec<Repair>, PuzzleError> {
+    let content = puzzle::read_input(&config.puzzle_input)?;
+    let reports = get_reports(&content).map_err(|e| e.in_file(&config.puzzle_input))?;
+
+    reports
+        .iter()
+        .map(|(line, values)| Repair::of(*line, values, &config.rules))
+        .collect::<Result<_, _>>()
+        .map_err(|e| e.in_file(&config.puzzle_input))
+}
+
+/// Repairs as an aligned table, followed by their total cost.
+pub fn repair_table(repairs: &[Repair]) -> String {
+    repair::table(repairs)
+}
+
+/// Repairs as CSV, followed by a row with their total cost.
+pub fn repair_csv(repairs: &[Repair]) -> String {
+    repair::to_csv(repairs)
+}
+
+/// Number of reports, of repaired ones and total cost of `repairs` as JSON.
+pub fn repair_summary_json(repairs: &[Repair]) -> String {
+    repair::summary_json(repairs)
 }
 
 // Note on printing during tests:
@@ -115,8 +415,6 @@ pub fn run(config: Config) -> Result<(i32, i32), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use io::BufReader;
-
     use super::*;
 
     #[test]
@@ -137,32 +435,44 @@ mod tests {
     fn day2_validate_parse_line() {
         let data = "7 6 4 2 1";
 
-        let values = get_values_from_line(data);
+        let values = get_values_from_line(data).unwrap();
         assert_eq!(values, vec![7, 6, 4, 2, 1]);
     }
 
+    #[test]
+    fn day2_validate_parse_invalid_line() {
+        let data = "\
+7 6 4 2 1
+
+1 2 x 8 9
+";
+        let (line_number, line) = get_lines(data)[1];
+        let error = get_values_from_line(line).unwrap_err().on_line(line_number);
+        assert_eq!(error.to_string(), "line 3, column 5: invalid number 'x'");
+    }
+
     #[test]
     fn day2_validate_not_oscillating() {
         let data = vec![7, 6, 4, 2, 1];
-        assert!(are_not_oscillating(&data));
+        assert!(are_not_oscillating(&data, &Rules::default()));
     }
 
     #[test]
     fn day2_validate_oscillating() {
         let data = vec![7, 6, 8, 3, 1];
-        assert!(!are_not_oscillating(&data));
+        assert!(!are_not_oscillating(&data, &Rules::default()));
     }
 
     #[test]
     fn day2_validate_gradual() {
         let data = vec![1, 2, 3, 4, 5];
-        assert!(are_all_gradual_changing(&data));
+        assert!(are_all_gradual_changing(&data, &Rules::default()));
     }
 
     #[test]
     fn day2_validate_is_safe() {
         let data = vec![7, 6, 4, 2, 1];
-        assert!(is_safe(&data));
+        assert!(is_safe(&data, &Rules::default()));
     }
 
     #[test]
@@ -177,9 +487,9 @@ mod tests {
 ";
         let lines = get_lines(data);
         let mut num_safe = 0;
-        for line in lines {
-            let values = get_values_from_line(line);
-            if is_safe(&values) {
+        for (_, line) in lines {
+            let values = get_values_from_line(line).unwrap();
+            if is_safe(&values, &Rules::default()) {
                 println!("Found safe: {values:?}");
                 num_safe += 1;
             } else {
@@ -202,9 +512,9 @@ mod tests {
 ";
         let lines = get_lines(data);
         let mut num_safe = 0;
-        for line in lines {
-            let values = get_values_from_line(line);
-            if is_safe_loose(&values) {
+        for (_, line) in lines {
+            let values = get_values_from_line(line).unwrap();
+            if is_safe_loose(&values, &Rules::default()) {
                 println!("Found safe: {values:?}");
                 num_safe += 1;
             } else {
@@ -219,8 +529,177 @@ mod tests {
     fn day2_validate_special_loose_cases() {
         let data = "\
 79 76 74 73 70 73";
-        let values = get_values_from_line(data.trim());
+        let values = get_values_from_line(data.trim()).unwrap();
         println!("{values:?}");
-        assert!(is_safe_loose(&values));
+        assert!(is_safe_loose(&values, &Rules::default()));
+    }
+
+    // Fewest levels to drop for a short report to be safe, trying every
+    // subset of kept levels
+    fn brute_force_removals(values: &[i32], rules: &Rules) -> usize {
+        (0..1u32 << values.len())
+            .filter(|mask| {
+                let kept: Vec<i32> = (0..values.len())
+                    .filter(|index| mask & (1 << index) != 0)
+                    .map(|index| values[index])
+                    .collect();
+                is_safe(&kept, rules)
+            })
+            .map(|mask| values.len() - mask.count_ones() as usize)
+            .min()
+            .unwrap()
+    }
+
+    #[test]
+    fn day2_removals_within_sample_input() {
+        let reports: [&[i32]; 6] = [
+            &[7, 6, 4, 2, 1],
+            &[1, 2, 7, 8, 9],
+            &[9, 7, 6, 2, 1],
+            &[1, 3, 2, 4, 5],
+            &[8, 6, 4, 4, 1],
+            &[1, 3, 6, 7, 9],
+        ];
+
+        let rules = Rules::default();
+        let removals: Vec<Option<Vec<usize>>> = reports
+            .iter()
+            .map(|r| removals_within(r, 1, &rules))
+            .collect();
+        assert_eq!(
+            removals,
+            [
+                Some(vec![]),
+                None,
+                None,
+                Some(vec![1]),
+                Some(vec![3]),
+                Some(vec![])
+            ]
+        );
+
+        assert_eq!(
+            removals_within(&[1, 2, 7, 8, 9], 2, &rules),
+            Some(vec![0, 1])
+        );
+        assert_eq!(removals_within(&[1, 2, 7, 8, 9], 0, &rules), None);
+        assert_eq!(removals_within(&[], 0, &rules), Some(vec![]));
+    }
+
+    #[test]
+    fn day2_removals_within_matches_brute_force() {
+        let mut seed: u32 = 2024;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % 10
+        };
+
+        let all_rules = [
+            Rules::default(),
+            Rules {
+                min_step: 1,
+                max_step: 2,
+                direction: Direction::Increasing,
+                max_direction_changes: 0,
+            },
+            Rules {
+                min_step: 2,
+                max_step: 5,
+                direction: Direction::Decreasing,
+                max_direction_changes: 1,
+            },
+            Rules {
+                min_step: 1,
+                max_step: 4,
+                direction: Direction::NonStrict,
+                max_direction_changes: 2,
+            },
+            Rules {
+                min_step: 3,
+                max_step: 1000,
+                direction: Direction::Either,
+                max_direction_changes: 1,
+            },
+        ];
+
+        for _ in 0..300 {
+            let length = next() as usize % 8;
+            let values: Vec<i32> = (0..length).map(|_| next() as i32).collect();
+
+            for rules in &all_rules {
+                let fewest = brute_force_removals(&values, rules);
+                for k in 0..=3 {
+                    let removals = removals_within(&values, k, rules);
+                    assert_eq!(
+                        removals.as_ref().map(|r| r.len()),
+                        Some(fewest).filter(|&fewest| fewest <= k),
+                        "{values:?} k={k} {rules:?}"
+                    );
+
+                    if let Some(removals) = removals {
+                        let kept: Vec<i32> = (0..values.len())
+                            .filter(|index| !removals.contains(index))
+                            .map(|index| values[index])
+                            .collect();
+                        assert!(is_safe(&kept, rules), "{values:?} without {removals:?}");
+                    }
+                }
+            }
+
+            let rules = Rules::default();
+            assert_eq!(
+                is_safe_loose(&values, &rules),
+                brute_force_removals(&values, &rules) <= 1
+            );
+        }
+    }
+
+    #[test]
+    fn day2_removals_within_wide_steps() {
+        // a long rise with every hundredth level dropping back to 0
+        let values: Vec<i32> = (0..10_000)
+            .map(|index| if index % 100 == 0 { 0 } else { index * 7 })
+            .collect();
+        let rules = Rules {
+            max_step: 1_000_000,
+            direction: Direction::Increasing,
+            ..Rules::default()
+        };
+
+        let spikes: Vec<usize> = (100..10_000).step_by(100).collect();
+        assert_eq!(removals_within(&values, 100, &rules), Some(spikes));
+        assert_eq!(removals_within(&values, 98, &rules), None);
+    }
+
+    #[test]
+    fn day2_configurable_rules() {
+        let rules = |direction, max_direction_changes| Rules {
+            min_step: 1,
+            max_step: 3,
+            direction,
+            max_direction_changes,
+        };
+
+        let down = [9, 7, 6, 4];
+        assert!(is_safe(&down, &rules(Direction::Either, 0)));
+        assert!(is_safe(&down, &rules(Direction::Decreasing, 0)));
+        assert!(!is_safe(&down, &rules(Direction::Increasing, 0)));
+
+        let flat = [1, 2, 2, 4];
+        assert!(!is_safe(&flat, &rules(Direction::Either, 0)));
+        assert!(is_safe(&flat, &rules(Direction::NonStrict, 0)));
+
+        let turning = [1, 3, 2, 4, 2];
+        assert!(!is_safe(&turning, &rules(Direction::Either, 2)));
+        assert!(is_safe(&turning, &rules(Direction::Either, 3)));
+        assert!(is_safe(&turning, &rules(Direction::Increasing, 3)));
+        assert!(!is_safe(&turning, &rules(Direction::Decreasing, 3)));
+
+        let wide = Rules {
+            max_step: 5,
+            ..Rules::default()
+        };
+        assert!(is_safe(&[1, 2, 7, 8, 9], &wide));
+        assert_eq!(removals_within(&[1, 2, 7, 8, 9], 1, &wide), Some(vec![]));
     }
 }
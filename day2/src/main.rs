@@ -1,13 +1,61 @@
-use std::{env, error};
+use day2::Mode;
+use puzzle::output::{Format, Output};
+use std::env;
+use std::time::Instant;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    println!("Hello, day 2!");
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let output = Output::from_args(&mut args);
+    let input = args.get(1).cloned();
 
-    let args: Vec<String> = env::args().collect();
-    let config = day2::Config::build(&args)?;
-    let (num_safe, num_loosely_safe) = day2::run(config)?;
+    if output.is_text() {
+        println!("Hello, day 2!");
+    }
 
-    println!("Num safe:         {num_safe}");
-    println!("Num loosely safe: {num_loosely_safe}");
-    Ok(())
+    let config = day2::Config::build(&args)
+        .unwrap_or_else(|err| output.fail(2, input.as_deref(), "Problem parsing arguments", &err));
+
+    match config.mode() {
+        Mode::Counts => (),
+        Mode::Report => {
+            let diagnostics = day2::run_report(config)
+                .unwrap_or_else(|err| output.fail(2, input.as_deref(), "Application error", &err));
+            match output.format() {
+                Format::Text => print!("{}", day2::report_table(&diagnostics)),
+                Format::Json => diagnostics.iter().for_each(|d| println!("{}", d.to_json())),
+                Format::Csv => print!("{}", day2::report_csv(&diagnostics)),
+            }
+            return;
+        }
+        Mode::Repair => {
+            let repairs = day2::run_repair(config)
+                .unwrap_or_else(|err| output.fail(2, input.as_deref(), "Application error", &err));
+            match output.format() {
+                Format::Text => print!("{}", day2::repair_table(&repairs)),
+                Format::Json => {
+                    repairs.iter().for_each(|r| println!("{}", r.to_json()));
+                    println!("{}", day2::repair_summary_json(&repairs));
+                }
+                Format::Csv => print!("{}", day2::repair_csv(&repairs)),
+            }
+            return;
+        }
+    }
+
+    let start = Instant::now();
+    let (num_safe, num_loosely_safe) = day2::run(config)
+        .unwrap_or_else(|err| output.fail(2, input.as_deref(), "Application error", &err));
+    let elapsed = start.elapsed();
+
+    if output.is_text() {
+        println!("Num safe:         {num_safe}");
+        println!("Num loosely safe: {num_loosely_safe}");
+    } else {
+        output.answers(
+            2,
+            input.as_deref().unwrap_or_default(),
+            &[&num_safe, &num_loosely_safe],
+            elapsed,
+        );
+    }
 }
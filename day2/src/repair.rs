@@ -0,0 +1,784 @@
+use crate::{Direction, Rules, Trend};
+use puzzle::PuzzleError;
+use serde::Serialize;
+
+/// Most levels in a report [`Repair::of`] repairs. It tries every way of
+/// stepping between every pair of levels, which takes a time growing
+/// steeply with the length of the report.
+pub const MAX_REPAIR_LEVELS: usize = 32;
+
+/// A level given a new value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct Change {
+    pub index: usize,
+    pub from: i32,
+    pub to: i32,
+}
+
+/// The fewest level values to change for a report to become safe, along
+/// with the report once repaired. Levels are referred to by their
+/// zero-based index in the report.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Repair {
+    /// One-based line of the report in the input.
+    pub line: usize,
+    /// `None` when no levels in the range of an `i32` make the report safe,
+    /// the report then being left as it is.
+    pub cost: Option<usize>,
+    pub changes: Vec<Change>,
+    pub levels: Vec<i32>,
+}
+
+// Steps a repair may use between two levels
+struct Steps {
+    // smallest and largest size of steps changing the level
+    min: i64,
+    max: i64,
+    // whether levels may also stay flat
+    flat: bool,
+}
+
+impl Steps {
+    fn of(rules: &Rules) -> Steps {
+        Steps {
+            min: i64::from(rules.min_step.max(1)),
+            max: i64::from(rules.max_step),
+            flat: rules.direction == Direction::NonStrict,
+        }
+    }
+}
+
+// How the steps between two levels go: `runs` runs alternating between
+// going up and down from `first` on, then the flat steps. Where the runs
+// start and end does not matter to the rules, only how many there are.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Shape {
+    first: Trend,
+    runs: usize,
+    ups: usize,
+    downs: usize,
+    flats: usize,
+}
+
+impl Shape {
+    // Every shape of `gap` steps with at most `max_runs` runs
+    fn all(gap: usize, steps: &Steps, max_runs: usize) -> Vec<Shape> {
+        let mut shapes = Vec::new();
+        if steps.flat || gap == 0 {
+            shapes.push(Shape {
+                first: Trend::Flat,
+                runs: 0,
+                ups: 0,
+                downs: 0,
+                flats: gap,
+            });
+        }
+        if steps.min > steps.max {
+            return shapes;
+        }
+
+        for first in [Trend::Up, Trend::Down] {
+            for runs in 1..=max_runs.min(gap) {
+                let (first_runs, other_runs) = (runs.div_ceil(2), runs / 2);
+                let (up_runs, down_runs) = match first {
+                    Trend::Up => (first_runs, other_runs),
+                    _ => (other_runs, first_runs),
+                };
+                let movings = if steps.flat { runs..=gap } else { gap..=gap };
+                for moving in movings {
+                    for ups in up_runs..=moving - down_runs {
+                        let downs = moving - ups;
+                        if (up_runs == 0 && ups > 0) || (down_runs == 0 && downs > 0) {
+                            continue;
+                        }
+                        shapes.push(Shape {
+                            first,
+                            runs,
+                            ups,
+                            downs,
+                            flats: gap - moving,
+                        });
+                    }
+                }
+            }
+        }
+
+        shapes
+    }
+
+    // Way of every step, the first run of each way taking its extra steps
+    fn trends(&self) -> Vec<Trend> {
+        let other = match self.first {
+            Trend::Up => Trend::Down,
+            _ => Trend::Up,
+        };
+        let (first_runs, other_runs) = (self.runs.div_ceil(2), self.runs / 2);
+        let (first_steps, other_steps) = match self.first {
+            Trend::Up => (self.ups, self.downs),
+            _ => (self.downs, self.ups),
+        };
+
+        let mut trends = Vec::with_capacity(self.ups + self.downs + self.flats);
+        for run in 0..self.runs {
+            let (trend, length) = match (run % 2, run) {
+                (0, 0) => (self.first, first_steps + 1 - first_runs),
+                (0, _) => (self.first, 1),
+                (_, 1) => (other, other_steps + 1 - other_runs),
+                _ => (other, 1),
+            };
+            trends.extend(std::iter::repeat_n(trend, length));
+        }
+        trends.extend(std::iter::repeat_n(Trend::Flat, self.flats));
+        trends
+    }
+
+    // The trend and direction changes after the steps, if the rules
+    // allow them. Steps going the same way as the one before change
+    // nothing, so a run is followed as if it were a single step.
+    fn follow(&self, (trend, used): (Trend, usize), rules: &Rules) -> Option<(Trend, usize)> {
+        let other = match self.first {
+            Trend::Up => Trend::Down,
+            _ => Trend::Up,
+        };
+        let runs = (0..self.runs).map(|run| if run % 2 == 0 { self.first } else { other });
+        let flats = (self.flats > 0).then_some(Trend::Flat);
+        runs.chain(flats).try_fold((trend, used), |(trend, used), step| {
+            trend.follow(step, used, rules)
+        })
+    }
+
+    // Whether the steps can add up to `rise`
+    fn reaches(&self, rise: i64, steps: &Steps) -> bool {
+        let (ups, downs) = (self.ups as i64, self.downs as i64);
+        ups * steps.min - downs * steps.max <= rise && rise <= ups * steps.max - downs * steps.min
+    }
+
+    // The steps adding up to `rise`, or all of the smallest size without
+    // one. Extra size goes to the first steps of the way that needs it.
+    fn rises(&self, rise: Option<i64>, steps: &Steps) -> Vec<i64> {
+        let trends = self.trends();
+        let base = (self.ups as i64 - self.downs as i64) * steps.min;
+        let mut extra = rise.map_or(0, |rise| rise - base);
+        trends
+            .iter()
+            .map(|&trend| {
+                let grown = match trend {
+                    Trend::Up if extra > 0 => extra.min(steps.max - steps.min),
+                    Trend::Down if extra < 0 => (-extra).min(steps.max - steps.min),
+                    _ => 0,
+                };
+                match trend {
+                    Trend::Up => {
+                        extra -= grown;
+                        steps.min + grown
+                    }
+                    Trend::Down => {
+                        extra += grown;
+                        -(steps.min + grown)
+                    }
+                    Trend::Flat => 0,
+                }
+            })
+            .collect()
+    }
+
+    // Whether the levels the steps go through from `start` all fit in an
+    // `i32`, the steps being taken back from it when `back` is set
+    fn fits(&self, start: i64, rise: Option<i64>, back: bool, steps: &Steps) -> bool {
+        let reach = (self.ups + self.downs) as i64 * steps.max;
+        if fits(start - reach) && fits(start + reach) {
+            return true;
+        }
+
+        let sign = if back { -1 } else { 1 };
+        self.rises(rise, steps)
+            .iter()
+            .scan(start, |level, rise| {
+                *level += sign * rise;
+                Some(*level)
+            })
+            .all(fits)
+    }
+}
+
+fn fits(level: i64) -> bool {
+    i32::try_from(level).is_ok()
+}
+
+// Levels all given new values, placed around 0, for a report of `length`
+// levels none of which can be kept
+fn fresh(length: usize, shapes: &[Shape], steps: &Steps, rules: &Rules) -> Option<Vec<i64>> {
+    shapes
+        .iter()
+        .filter(|shape| shape.follow((Trend::Flat, 0), rules).is_some())
+        .find_map(|shape| {
+            let mut levels = Vec::with_capacity(length);
+            levels.push(0);
+            for rise in shape.rises(None, steps) {
+                levels.push(levels[levels.len() - 1] + rise);
+            }
+            let low = levels.iter().min()?;
+            let high = levels.iter().max()?;
+            let start = -(low + high).div_euclid(2);
+            let levels: Vec<i64> = levels.iter().map(|level| level + start).collect();
+            levels.iter().all(|&level| fits(level)).then_some(levels)
+        })
+}
+
+// Most levels kept by a repair ending at some index in some state
+#[derive(Debug, Clone, Copy)]
+struct Kept {
+    length: usize,
+    // index and state of the kept level before, if any
+    previous: Option<(usize, usize)>,
+    // steps from that level, or from the first one
+    shape: Shape,
+}
+
+impl Repair {
+    /// Finds a repair keeping as many levels as possible, then fills the
+    /// others in. Repairs turn at most as often as the rules allow, so a
+    /// safe report is left as it is.
+    ///
+    /// A state is a trend along with the direction changes used to reach
+    /// it, as for [`removals_within`](crate::removals_within); a kept level
+    /// extends a chain ending at an earlier one when some shape of the
+    /// steps in between bridges the gap, without any level filled in going
+    /// out of the range of an `i32`. Every pair of levels being tried,
+    /// reports of more than [`MAX_REPAIR_LEVELS`] levels are refused.
+    pub fn of(line: usize, values: &[i32], rules: &Rules) -> Result<Repair, PuzzleError> {
+        if values.len() > MAX_REPAIR_LEVELS {
+            return Err(PuzzleError::invalid(format!(
+                "cannot repair reports of more than {MAX_REPAIR_LEVELS} levels, found {}",
+                values.len()
+            ))
+            .on_line(line));
+        }
+
+        let steps = Steps::of(rules);
+        let wide: Vec<i64> = values.iter().map(|v| i64::from(*v)).collect();
+
+        // turning more often than there are steps is never needed
+        let changes = rules.max_direction_changes.min(values.len()) + 1;
+        let state_of = |trend: Trend, used: usize| trend as usize * changes + used;
+        let trend_of = |state: usize| (Trend::ALL[state / changes], state % changes);
+        let states = Trend::ALL.len() * changes;
+        let shapes: Vec<Vec<Shape>> = (0..wide.len())
+            .map(|gap| Shape::all(gap, &steps, changes))
+            .collect();
+
+        let mut chains: Vec<Vec<Option<Kept>>> = Vec::with_capacity(wide.len());
+        for (index, value) in wide.iter().enumerate() {
+            let mut current: Vec<Option<Kept>> = vec![None; states];
+            // of chains as long, the one through the latest levels
+            let mut offer = |state: (Trend, usize), kept: Kept| {
+                let best = &mut current[state_of(state.0, state.1)];
+                if best.is_none_or(|best| best.length <= kept.length) {
+                    *best = Some(kept);
+                }
+            };
+
+            // the first kept level, after levels all given new values
+            for shape in &shapes[index] {
+                if !shape.fits(*value, None, true, &steps) {
+                    continue;
+                }
+                if let Some(state) = shape.follow((Trend::Flat, 0), rules) {
+                    let shape = *shape;
+                    offer(
+                        state,
+                        Kept {
+                            length: 1,
+                            previous: None,
+                            shape,
+                        },
+                    );
+                }
+            }
+
+            for (previous, chain) in chains.iter().enumerate() {
+                let rise = value - wide[previous];
+                for (state, kept) in chain.iter().enumerate() {
+                    let Some(kept) = kept else {
+                        continue;
+                    };
+                    for shape in &shapes[index - previous] {
+                        if !shape.reaches(rise, &steps)
+                            || !shape.fits(wide[previous], Some(rise), false, &steps)
+                        {
+                            continue;
+                        }
+                        if let Some(next) = shape.follow(trend_of(state), rules) {
+                            let kept = Kept {
+                                length: kept.length + 1,
+                                previous: Some((previous, state)),
+                                shape: *shape,
+                            };
+                            offer(next, kept);
+                        }
+                    }
+                }
+            }
+            chains.push(current);
+        }
+
+        // the last kept level, before levels all given new values
+        let mut end: Option<(usize, usize, Shape)> = None;
+        let mut longest = 0;
+        for (index, chain) in chains.iter().enumerate() {
+            for (state, kept) in chain.iter().enumerate() {
+                let Some(kept) = kept.filter(|kept| kept.length > longest) else {
+                    continue;
+                };
+                let tail = shapes[wide.len() - 1 - index].iter().find(|shape| {
+                    shape.follow(trend_of(state), rules).is_some()
+                        && shape.fits(wide[index], None, false, &steps)
+                });
+                if let Some(tail) = tail {
+                    longest = kept.length;
+                    end = Some((index, state, *tail));
+                }
+            }
+        }
+
+        let repaired = match end {
+            Some(end) => Some(Repair::fill(&wide, &chains, end, &steps)),
+            None if wide.is_empty() => Some(Vec::new()),
+            None => fresh(wide.len(), &shapes[wide.len() - 1], &steps, rules),
+        };
+        let Some(repaired) = repaired else {
+            return Ok(Repair {
+                line,
+                cost: None,
+                changes: Vec::new(),
+                levels: values.to_vec(),
+            });
+        };
+
+        let levels: Vec<i32> = repaired
+            .iter()
+            .map(|&level| i32::try_from(level).expect("repairs only use levels that fit"))
+            .collect();
+        let changes: Vec<Change> = values
+            .iter()
+            .zip(&levels)
+            .enumerate()
+            .filter(|(_, (from, to))| from != to)
+            .map(|(index, (&from, &to))| Change { index, from, to })
+            .collect();
+
+        Ok(Repair {
+            line,
+            cost: Some(changes.len()),
+            changes,
+            levels,
+        })
+    }
+
+    // The levels of the report with those not kept filled in, for the
+    // chain ending at `last` in `state` and the `tail` steps after it
+    fn fill(
+        wide: &[i64],
+        chains: &[Vec<Option<Kept>>],
+        (last, state, tail): (usize, usize, Shape),
+        steps: &Steps,
+    ) -> Vec<i64> {
+        let mut repaired = wide.to_vec();
+        for (offset, rise) in tail.rises(None, steps).iter().enumerate() {
+            let index = last + offset + 1;
+            repaired[index] = repaired[index - 1] + rise;
+        }
+
+        let mut link = Some((last, state));
+        while let Some((index, state)) = link {
+            let kept = chains[index][state].expect("chains link kept levels");
+            match kept.previous {
+                Some((previous, _)) => {
+                    let rises = kept.shape.rises(Some(wide[index] - wide[previous]), steps);
+                    for (offset, rise) in rises.iter().enumerate().take(index - previous - 1) {
+                        let level = previous + offset + 1;
+                        repaired[level] = repaired[level - 1] + rise;
+                    }
+                }
+                None => {
+                    let rises = kept.shape.rises(None, steps);
+                    for level in (0..index).rev() {
+                        repaired[level] = repaired[level + 1] - rises[level];
+                    }
+                }
+            }
+            link = kept.previous;
+        }
+
+        repaired
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("repairs are plain data")
+    }
+
+    // fields as printed in tables and CSV
+    fn fields(&self) -> [String; 4] {
+        let changes: Vec<String> = self
+            .changes
+            .iter()
+            .map(|c| format!("{}:{}->{}", c.index, c.from, c.to))
+            .collect();
+        let levels: Vec<String> = self.levels.iter().map(|l| l.to_string()).collect();
+        match self.cost {
+            Some(cost) => [
+                self.line.to_string(),
+                cost.to_string(),
+                changes.join(" "),
+                levels.join(" "),
+            ],
+            None => [
+                self.line.to_string(),
+                "-".to_string(),
+                "unrepairable".to_string(),
+                levels.join(" "),
+            ],
+        }
+    }
+}
+
+const HEADER: [&str; 4] = ["line", "cost", "changes", "levels"];
+
+/// Sum of the costs of `repairs`, unrepairable reports left aside.
+pub fn total_cost(repairs: &[Repair]) -> usize {
+    repairs.iter().filter_map(|r| r.cost).sum()
+}
+
+// Number of reports given new levels and of those that cannot be
+fn counts(repairs: &[Repair]) -> (usize, usize) {
+    let repaired = repairs.iter().filter(|r| r.cost.is_some_and(|c| c > 0));
+    let unrepairable = repairs.iter().filter(|r| r.cost.is_none());
+    (repaired.count(), unrepairable.count())
+}
+
+/// The aggregate of `repairs` as JSON.
+pub fn summary_json(repairs: &[Repair]) -> String {
+    let (repaired, unrepairable) = counts(repairs);
+    serde_json::json!({
+        "reports": repairs.len(),
+        "repaired": repaired,
+        "unrepairable": unrepairable,
+        "total_cost": total_cost(repairs),
+    })
+    .to_string()
+}
+
+/// Repairs as an aligned table, followed by their total cost.
+pub fn table(repairs: &[Repair]) -> String {
+    let rows: Vec<[String; 4]> = repairs.iter().map(|r| r.fields()).collect();
+    let mut widths = HEADER.map(str::len);
+    for row in &rows {
+        for (width, field) in widths.iter_mut().zip(row) {
+            *width = (*width).max(field.len());
+        }
+    }
+
+    let mut table = String::new();
+    let mut push_row = |fields: [&str; 4]| {
+        let line: Vec<String> = fields
+            .iter()
+            .zip(widths)
+            .enumerate()
+            .map(|(column, (field, width))| match column {
+                // numbers are right-aligned
+                0 | 1 => format!("{field:>width$}"),
+                _ => format!("{field:<width$}"),
+            })
+            .collect();
+        table.push_str(line.join("  ").trim_end());
+        table.push('\n');
+    };
+
+    push_row(HEADER);
+    for row in &rows {
+        push_row(row.each_ref().map(String::as_str));
+    }
+
+    let (repaired, unrepairable) = counts(repairs);
+    table.push_str(&format!(
+        "Total cost: {} level(s) changed in {repaired} of {} report(s)",
+        total_cost(repairs),
+        repairs.len()
+    ));
+    if unrepairable > 0 {
+        table.push_str(&format!(", {unrepairable} unrepairable"));
+    }
+    table.push('\n');
+    table
+}
+
+/// Repairs as CSV with a header row and a last `total` row.
+pub fn to_csv(repairs: &[Repair]) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(HEADER).expect("writing to memory");
+    for repair in repairs {
+        writer
+            .write_record(repair.fields())
+            .expect("writing to memory");
+    }
+    writer
+        .write_record(["total", &total_cost(repairs).to_string(), "", ""])
+        .expect("writing to memory");
+
+    let bytes = writer.into_inner().expect("writing to memory");
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::is_safe;
+
+    // Whether changing at most `budget` levels, to values close to the
+    // others, can make a short report safe
+    fn brute_force_repairable(values: &mut Vec<i32>, budget: usize, rules: &Rules) -> bool {
+        if is_safe(values, rules) {
+            return true;
+        }
+        if budget == 0 {
+            return false;
+        }
+
+        let low = values.iter().min().unwrap() - 12;
+        let high = values.iter().max().unwrap() + 12;
+        for index in 0..values.len() {
+            let original = values[index];
+            for candidate in low..=high {
+                if candidate == original {
+                    continue;
+                }
+                values[index] = candidate;
+                let repairable = brute_force_repairable(values, budget - 1, rules);
+                values[index] = original;
+                if repairable {
+                    return true;
+                }
+            }
+        }
+
+        false
+    }
+
+    #[test]
+    fn repair_sample_input() {
+        let reports: [&[i32]; 6] = [
+            &[7, 6, 4, 2, 1],
+            &[1, 2, 7, 8, 9],
+            &[9, 7, 6, 2, 1],
+            &[1, 3, 2, 4, 5],
+            &[8, 6, 4, 4, 1],
+            &[1, 3, 6, 7, 9],
+        ];
+
+        let rules = Rules::default();
+        let repairs: Vec<Repair> = reports
+            .iter()
+            .enumerate()
+            .map(|(index, values)| Repair::of(index + 1, values, &rules).unwrap())
+            .collect();
+
+        let costs: Vec<usize> = repairs.iter().filter_map(|r| r.cost).collect();
+        assert_eq!(costs, [0, 1, 1, 2, 1, 0]);
+        assert_eq!(total_cost(&repairs), 5);
+        for repair in &repairs {
+            assert!(is_safe(&repair.levels, &rules), "{repair:?}");
+        }
+
+        assert_eq!(
+            repairs[1].changes,
+            [Change {
+                index: 1,
+                from: 2,
+                to: 4
+            }]
+        );
+        assert_eq!(repairs[1].levels, [1, 4, 7, 8, 9]);
+    }
+
+    #[test]
+    fn repair_matches_brute_force() {
+        let mut seed: u32 = 18;
+        let mut next = || {
+            seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12345);
+            (seed >> 16) % 10
+        };
+
+        let all_rules = [
+            Rules::default(),
+            Rules {
+                min_step: 2,
+                max_step: 3,
+                direction: Direction::Increasing,
+                max_direction_changes: 0,
+            },
+            Rules {
+                min_step: 1,
+                max_step: 2,
+                direction: Direction::NonStrict,
+                max_direction_changes: 0,
+            },
+            Rules {
+                min_step: 1,
+                max_step: 3,
+                direction: Direction::Either,
+                max_direction_changes: 1,
+            },
+            Rules {
+                min_step: 2,
+                max_step: 4,
+                direction: Direction::Decreasing,
+                max_direction_changes: 2,
+            },
+            Rules {
+                min_step: 1,
+                max_step: 2,
+                direction: Direction::NonStrict,
+                max_direction_changes: 1,
+            },
+        ];
+
+        for _ in 0..40 {
+            let length = 1 + next() as usize % 5;
+            let mut values: Vec<i32> = (0..length).map(|_| next() as i32).collect();
+
+            for rules in &all_rules {
+                let repair = Repair::of(1, &values, rules).unwrap();
+                assert!(is_safe(&repair.levels, rules), "{values:?} {repair:?}");
+
+                let cost = repair.cost.unwrap();
+                for budget in 0..cost.min(3) {
+                    assert!(
+                        !brute_force_repairable(&mut values, budget, rules),
+                        "{values:?} repairable with {budget} changes, not {cost}"
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn repair_turns_as_allowed() {
+        let rules = Rules {
+            max_direction_changes: 1,
+            ..Rules::default()
+        };
+        let peak = Repair::of(1, &[1, 2, 3, 2, 1], &rules).unwrap();
+        assert_eq!(peak.cost, Some(0));
+        assert_eq!(peak.levels, [1, 2, 3, 2, 1]);
+
+        // the turn is kept, the jump down is bridged
+        let repair = Repair::of(1, &[1, 2, 3, 9, 1], &rules).unwrap();
+        assert_eq!(repair.cost, Some(1));
+        assert!(is_safe(&repair.levels, &rules), "{repair:?}");
+        assert!(!is_safe(&repair.levels, &Rules::default()));
+    }
+
+    #[test]
+    fn repairs_stay_within_i32() {
+        let rules = Rules::default();
+        for level in [i32::MAX, i32::MIN] {
+            let repair = Repair::of(1, &[level; 3], &rules).unwrap();
+            assert_eq!(repair.cost, Some(2), "{repair:?}");
+            assert!(is_safe(&repair.levels, &rules), "{repair:?}");
+        }
+
+        let increasing = Rules {
+            direction: Direction::Increasing,
+            ..Rules::default()
+        };
+        let repair = Repair::of(1, &[i32::MAX, 0], &increasing).unwrap();
+        assert_eq!(repair.levels, [-1, 0]);
+
+        // neither level can be kept, both are given new ones
+        let repair = Repair::of(1, &[i32::MAX, i32::MIN], &increasing).unwrap();
+        assert_eq!(repair.cost, Some(2));
+        assert!(is_safe(&repair.levels, &increasing), "{repair:?}");
+
+        let decreasing = Rules {
+            direction: Direction::Decreasing,
+            ..Rules::default()
+        };
+        let repair = Repair::of(1, &[0, i32::MIN, 5], &decreasing).unwrap();
+        assert_eq!(repair.cost, Some(2));
+        assert!(is_safe(&repair.levels, &decreasing), "{repair:?}");
+    }
+
+    #[test]
+    fn unrepairable_reports() {
+        // two steps of 2^31 span more than an i32
+        let rules = Rules {
+            min_step: 1 << 31,
+            max_step: 1 << 31,
+            direction: Direction::Increasing,
+            max_direction_changes: 0,
+        };
+        let repair = Repair::of(3, &[0, 1, 2], &rules).unwrap();
+        assert_eq!(repair.cost, None);
+        assert_eq!(repair.levels, [0, 1, 2]);
+        assert!(repair.changes.is_empty());
+
+        let repairs = [repair, Repair::of(4, &[0], &rules).unwrap()];
+        assert_eq!(
+            table(&repairs),
+            "\
+line  cost  changes       levels
+   3     -  unrepairable  0 1 2
+   4     0                0
+Total cost: 0 level(s) changed in 0 of 2 report(s), 1 unrepairable
+"
+        );
+        assert_eq!(
+            summary_json(&repairs),
+            r#"{"reports":2,"repaired":0,"unrepairable":1,"total_cost":0}"#
+        );
+
+        let long = vec![1; MAX_REPAIR_LEVELS + 1];
+        assert_eq!(
+            Repair::of(7, &long, &Rules::default()).unwrap_err().to_string(),
+            format!(
+                "line 7, column 1: cannot repair reports of more than {MAX_REPAIR_LEVELS} levels, \
+                 found {}",
+                MAX_REPAIR_LEVELS + 1
+            )
+        );
+    }
+
+    #[test]
+    fn repairs_output() {
+        let repairs = [
+            Repair::of(1, &[7, 6, 4, 2, 1], &Rules::default()).unwrap(),
+            Repair::of(2, &[1, 2, 7, 8, 9], &Rules::default()).unwrap(),
+        ];
+
+        assert_eq!(
+            repairs[1].to_json(),
+            r#"{"line":2,"cost":1,"changes":[{"index":1,"from":2,"to":4}],"levels":[1,4,7,8,9]}"#
+        );
+        assert_eq!(
+            summary_json(&repairs),
+            r#"{"reports":2,"repaired":1,"unrepairable":0,"total_cost":1}"#
+        );
+        assert_eq!(
+            table(&repairs),
+            "\
+line  cost  changes  levels
+   1     0           7 6 4 2 1
+   2     1  1:2->4   1 4 7 8 9
+Total cost: 1 level(s) changed in 1 of 2 report(s)
+"
+        );
+        assert_eq!(
+            to_csv(&repairs),
+            "line,cost,changes,levels\n1,0,,7 6 4 2 1\n2,1,1:2->4,1 4 7 8 9\ntotal,1,,\n"
+        );
+    }
+}
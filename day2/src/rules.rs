@@ -0,0 +1,238 @@
+use puzzle::PuzzleError;
+use serde::Deserialize;
+
+/// Which way the levels of a report may go.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Direction {
+    /// Every step goes up.
+    Increasing,
+    /// Every step goes down.
+    Decreasing,
+    /// Every step goes up, or every step goes down.
+    Either,
+    /// Like `Either`, but levels may also stay flat, whatever the step
+    /// bounds.
+    NonStrict,
+}
+
+impl Direction {
+    fn parse(value: &str) -> Result<Direction, PuzzleError> {
+        match value {
+            "increasing" => Ok(Direction::Increasing),
+            "decreasing" => Ok(Direction::Decreasing),
+            "either" => Ok(Direction::Either),
+            "non-strict" => Ok(Direction::NonStrict),
+            _ => Err(PuzzleError::usage(format!(
+                "Invalid direction '{value}': must be increasing, decreasing, either or non-strict"
+            ))),
+        }
+    }
+}
+
+/// What makes a report safe, as stored in a rules file:
+///
+/// ```toml
+/// min_step = 1
+/// max_step = 3
+/// direction = "either"
+/// max_direction_changes = 0
+/// ```
+///
+/// Missing settings keep the values of the puzzle.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Rules {
+    /// Smallest difference allowed between two adjacent levels, only 0
+    /// with the non-strict direction.
+    pub min_step: u32,
+    /// Largest difference allowed between two adjacent levels.
+    pub max_step: u32,
+    pub direction: Direction,
+    /// How many times the levels may turn from going up to going down, or
+    /// the other way round.
+    pub max_direction_changes: usize,
+}
+
+impl Default for Rules {
+    fn default() -> Rules {
+        Rules {
+            min_step: 1,
+            max_step: 3,
+            direction: Direction::Either,
+            max_direction_changes: 0,
+        }
+    }
+}
+
+fn parse_flag<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, PuzzleError> {
+    value
+        .parse()
+        .map_err(|_| PuzzleError::usage(format!("Invalid value '{value}' for {flag}")))
+}
+
+impl Rules {
+    pub fn parse(raw_content: &str) -> Result<Rules, PuzzleError> {
+        let rules: Rules = toml::from_str(raw_content).map_err(|e| {
+            let description = e.message().to_string();
+            match e.span() {
+                Some(span) => {
+                    let location = puzzle::location_of(raw_content, span.start);
+                    PuzzleError::at(location.line, location.column, description)
+                }
+                None => PuzzleError::invalid(description),
+            }
+        })?;
+
+        rules.validate()
+    }
+
+    pub fn load(file: &str) -> Result<Rules, PuzzleError> {
+        let raw_content = puzzle::read_input(file)?;
+        Rules::parse(&raw_content).map_err(|e| e.in_file(file))
+    }
+
+    /// Rules from `--rules <file>`, overridden by `--min-step`,
+    /// `--max-step`, `--direction` and `--max-direction-changes`.
+    pub fn from_args(args: &[String]) -> Result<Rules, PuzzleError> {
+        let mut options = Vec::new();
+        let mut remaining = args.iter();
+        while let Some(flag) = remaining.next() {
+            match remaining.next() {
+                Some(value) => options.push((flag.as_str(), value.as_str())),
+                None => return Err(PuzzleError::usage(format!("Missing value after {flag}"))),
+            }
+        }
+
+        let mut rules = match options.iter().rev().find(|(flag, _)| *flag == "--rules") {
+            Some((_, file)) => Rules::load(file)?,
+            None => Rules::default(),
+        };
+
+        for (flag, value) in options {
+            match flag {
+                "--rules" => (),
+                "--min-step" => rules.min_step = parse_flag(flag, value)?,
+                "--max-step" => rules.max_step = parse_flag(flag, value)?,
+                "--direction" => rules.direction = Direction::parse(value)?,
+                "--max-direction-changes" => rules.max_direction_changes = parse_flag(flag, value)?,
+                _ => return Err(PuzzleError::usage(format!("Unknown option {flag}"))),
+            }
+        }
+
+        rules
+            .validate()
+            .map_err(|e| PuzzleError::usage(e.to_string()))
+    }
+
+    fn validate(self) -> Result<Rules, PuzzleError> {
+        if self.min_step > self.max_step {
+            return Err(PuzzleError::invalid(format!(
+                "min_step ({}) must not exceed max_step ({})",
+                self.min_step, self.max_step
+            )));
+        }
+        // flat steps are only ever allowed by the non-strict direction
+        if self.min_step == 0 && self.direction != Direction::NonStrict {
+            return Err(PuzzleError::invalid(
+                "min_step must be at least 1 unless the direction is non-strict",
+            ));
+        }
+
+        Ok(self)
+    }
+
+    /// Whether a step of `difference` between adjacent levels is allowed
+    /// by the bounds. Flat steps pass when the direction is non-strict.
+    pub fn allows_step(&self, difference: i64) -> bool {
+        if difference == 0 && self.direction == Direction::NonStrict {
+            return true;
+        }
+
+        let size = difference.unsigned_abs();
+        u64::from(self.min_step) <= size && size <= u64::from(self.max_step)
+    }
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn parse_rules_file() {
+        let rules = Rules::parse("max_step = 5\ndirection = \"non-strict\"\n").unwrap();
+        assert_eq!(
+            rules,
+            Rules {
+                min_step: 1,
+                max_step: 5,
+                direction: Direction::NonStrict,
+                max_direction_changes: 0,
+            }
+        );
+
+        assert_eq!(Rules::parse("").unwrap(), Rules::default());
+
+        let error = Rules::parse("min_step = 1\nmax_steps = 2\n").unwrap_err();
+        assert!(error.to_string().starts_with("line 2, column 1: "));
+
+        let error = Rules::parse("min_step = 4\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "min_step (4) must not exceed max_step (3)"
+        );
+    }
+
+    #[test]
+    fn flags_override_defaults() {
+        let rules = Rules::from_args(&args(&[
+            "--direction",
+            "increasing",
+            "--max-step",
+            "10",
+            "--max-direction-changes",
+            "2",
+        ]))
+        .unwrap();
+        assert_eq!(rules.direction, Direction::Increasing);
+        assert_eq!(rules.max_step, 10);
+        assert_eq!(rules.max_direction_changes, 2);
+        assert_eq!(rules.min_step, 1);
+
+        assert!(Rules::from_args(&args(&["--direction", "sideways"])).is_err());
+        assert!(Rules::from_args(&args(&["--min-step"])).is_err());
+        assert!(Rules::from_args(&args(&["--min-step", "5"])).is_err());
+        assert!(Rules::from_args(&args(&["--min-step", "0", "--max-step", "0"])).is_err());
+        let error = Rules::from_args(&args(&["--min-step", "0"])).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "min_step must be at least 1 unless the direction is non-strict"
+        );
+        assert!(Rules::parse("min_step = 0\ndirection = \"increasing\"\n").is_err());
+        let rules = Rules::from_args(&args(&["--min-step", "0", "--direction", "non-strict"]));
+        assert_eq!(rules.unwrap().min_step, 0);
+        assert!(Rules::from_args(&args(&["--speed", "5"])).is_err());
+    }
+
+    #[test]
+    fn step_bounds() {
+        let rules = Rules::default();
+        assert!(rules.allows_step(-3));
+        assert!(!rules.allows_step(0));
+        assert!(!rules.allows_step(4));
+
+        let rules = Rules {
+            direction: Direction::NonStrict,
+            ..Rules::default()
+        };
+        assert!(rules.allows_step(0));
+    }
+}
@@ -0,0 +1,25 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use day4::{Day4, PackedGrid};
+use puzzle::bench;
+
+fn day4(c: &mut Criterion) {
+    let raw_input = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/puzzle-input.txt"
+    ));
+    bench::phases::<Day4>(c, "day4", &raw_input);
+
+    let packed = PackedGrid::parse(raw_input.as_bytes()).unwrap();
+    let mut group = c.benchmark_group("day4/large");
+    group.bench_function("parse", |b| {
+        b.iter(|| PackedGrid::parse(black_box(raw_input.as_bytes())))
+    });
+    group.bench_function("part1", |b| {
+        b.iter(|| black_box(&packed).count_word("XMAS"))
+    });
+    group.bench_function("part2", |b| b.iter(|| black_box(&packed).count_x_mas()));
+    group.finish();
+}
+
+criterion_group!(benches, day4);
+criterion_main!(benches);
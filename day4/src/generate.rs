@@ -0,0 +1,470 @@
+use crate::search::{find_words, Dictionary, Direction, Walk, ALL_DIRECTIONS, DIAGONALS};
+use grid::{Grid, Position, NEIGHBOURS_4};
+use puzzle::PuzzleError;
+use std::collections::{HashMap, HashSet};
+
+// Tries before giving up: whole grids, placements of a word, and rounds of
+// refilling the cells of unwanted occurrences
+const ATTEMPTS: usize = 20;
+const PLACEMENTS: usize = 500;
+const REFILLS: usize = 200;
+
+/// What a generated word search must hold.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spec {
+    pub columns: usize,
+    pub rows: usize,
+    /// Words to hide, with how many times each must occur.
+    pub words: Vec<(String, usize)>,
+    /// Directions words are written in, and searched along.
+    pub directions: Vec<Direction>,
+    /// Letters filling the rest of the grid, the letters of the words when
+    /// empty.
+    pub alphabet: Vec<char>,
+    pub seed: u64,
+}
+
+// What is wrong with the value of `option`, as a usage error
+fn in_option(option: &str, error: PuzzleError) -> PuzzleError {
+    PuzzleError::usage(format!("Invalid value for {option} at {error}"))
+}
+
+fn parse_directions(value: &str) -> Result<Vec<Direction>, PuzzleError> {
+    match value {
+        "all" => Ok(ALL_DIRECTIONS.to_vec()),
+        "orthogonal" => Ok(NEIGHBOURS_4.to_vec()),
+        "diagonal" => Ok(DIAGONALS.to_vec()),
+        // right, down-right and down, for beginners
+        "forward" => Ok(vec![(1, 0), (1, 1), (0, 1)]),
+        _ => Err(PuzzleError::usage(format!(
+            "Invalid directions '{value}': must be all, orthogonal, diagonal or forward"
+        ))),
+    }
+}
+
+impl Spec {
+    /// A spec from `<columns>x<rows>` followed by `--words <word[:count],...>`,
+    /// `--seed <n>`, `--directions <all|orthogonal|diagonal|forward>` and
+    /// `--alphabet <letters>`. Words occur once by default.
+    pub(crate) fn parse(size: &str, options: &[String]) -> Result<Spec, PuzzleError> {
+        let (columns, rows) = size
+            .split_once('x')
+            .ok_or_else(|| PuzzleError::usage(format!("Invalid size '{size}': expected 10x10")))?;
+        let mut spec = Spec {
+            columns: puzzle::parse_number(size, columns).map_err(|e| in_option("--generate", e))?,
+            rows: puzzle::parse_number(size, rows).map_err(|e| in_option("--generate", e))?,
+            words: Vec::new(),
+            directions: ALL_DIRECTIONS.to_vec(),
+            alphabet: Vec::new(),
+            seed: 0,
+        };
+
+        let mut remaining = options.iter();
+        while let Some(option) = remaining.next() {
+            let Some(value) = remaining.next() else {
+                return Err(PuzzleError::usage(format!("Missing value after {option}")));
+            };
+            match option.as_str() {
+                "--words" => {
+                    for entry in value.split(',') {
+                        let word = match entry.split_once(':') {
+                            Some((word, count)) => (
+                                word,
+                                puzzle::parse_number(value, count)
+                                    .map_err(|e| in_option(option, e))?,
+                            ),
+                            None => (entry, 1),
+                        };
+                        spec.words.push((word.0.trim().to_string(), word.1));
+                    }
+                }
+                "--seed" => {
+                    spec.seed =
+                        puzzle::parse_number(value, value).map_err(|e| in_option(option, e))?
+                }
+                "--directions" => spec.directions = parse_directions(value)?,
+                "--alphabet" => spec.alphabet = value.chars().collect(),
+                _ => return Err(PuzzleError::usage(format!("Unknown option {option}"))),
+            }
+        }
+
+        spec.validate()
+            .map_err(|e| PuzzleError::usage(e.to_string()))
+    }
+
+    fn validate(self) -> Result<Spec, PuzzleError> {
+        if self.columns == 0 || self.rows == 0 {
+            return Err(PuzzleError::invalid("the grid must not be empty"));
+        }
+        if self.words.is_empty() {
+            return Err(PuzzleError::invalid("no words to hide"));
+        }
+        if self.directions.is_empty() {
+            return Err(PuzzleError::invalid("no directions to write words in"));
+        }
+
+        let mut seen = HashSet::new();
+        for (word, count) in &self.words {
+            if word.is_empty() {
+                return Err(PuzzleError::invalid("words must not be empty"));
+            }
+            if !seen.insert(word) {
+                return Err(PuzzleError::invalid(format!("'{word}' is listed twice")));
+            }
+            let length = word.chars().count();
+            let fits = |(dx, dy): &Direction| {
+                (*dx == 0 || length <= self.columns) && (*dy == 0 || length <= self.rows)
+            };
+            if !self.directions.iter().any(fits) {
+                return Err(PuzzleError::invalid(format!(
+                    "'{word}' does not fit in a {}x{} grid",
+                    self.columns, self.rows
+                )));
+            }
+
+            // read both ways wherever it fits, it occurs in pairs
+            let reversible = |&(dx, dy): &Direction| self.directions.contains(&(-dx, -dy));
+            if is_palindrome(word)
+                && count % 2 == 1
+                && self.directions.iter().filter(|d| fits(d)).all(reversible)
+            {
+                return Err(PuzzleError::invalid(format!(
+                    "'{word}' reads the same both ways, so it occurs an even number of times"
+                )));
+            }
+        }
+
+        Ok(self)
+    }
+
+    fn alphabet(&self) -> Vec<char> {
+        if !self.alphabet.is_empty() {
+            return self.alphabet.clone();
+        }
+
+        let mut letters: Vec<char> = self.words.iter().flat_map(|(w, _)| w.chars()).collect();
+        letters.sort_unstable();
+        letters.dedup();
+        letters
+    }
+}
+
+// SplitMix64: small, fast and the same on every platform, which keeps
+// generated grids stable for a seed
+struct Random(u64);
+
+impl Random {
+    fn next(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9e37_79b9_7f4a_7c15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^ (z >> 31)
+    }
+
+    fn below(&mut self, bound: usize) -> usize {
+        (self.next() % bound as u64) as usize
+    }
+
+    fn pick<T: Copy>(&mut self, items: &[T]) -> T {
+        items[self.below(items.len())]
+    }
+}
+
+// Words of more than one letter reading the same both ways
+fn is_palindrome(word: &str) -> bool {
+    word.chars().count() > 1 && word.chars().eq(word.chars().rev())
+}
+
+// Occurrences of every word as the cells it is read along, in order, as
+// `find_words` reports them: a palindrome read both ways occurs twice
+fn occurrences(
+    grid: &Grid<char>,
+    dictionary: &Dictionary,
+    directions: &[Direction],
+) -> HashMap<String, HashSet<Vec<Position>>> {
+    let mut occurrences: HashMap<String, HashSet<Vec<Position>>> = HashMap::new();
+    for m in find_words(grid, dictionary, directions, Walk::Straight) {
+        occurrences.entry(m.word).or_default().insert(m.cells);
+    }
+    occurrences
+}
+
+/// Number of times every word occurs in `grid` along `directions`, as
+/// [`find_words`] counts them: a palindrome read both ways occurs twice.
+pub fn count_occurrences(
+    grid: &Grid<char>,
+    words: &[String],
+    directions: &[Direction],
+) -> Vec<usize> {
+    let found = occurrences(grid, &Dictionary::new(words), directions);
+    words
+        .iter()
+        .map(|word| found.get(word).map_or(0, HashSet::len))
+        .collect()
+}
+
+// Writes `word` somewhere it fits, returning the cells of the occurrences
+// it makes: two for a palindrome also read backwards, which must not be
+// more than `remaining`
+fn place(
+    word: &[char],
+    spec: &Spec,
+    cells: &mut Grid<Option<char>>,
+    taken: &HashSet<Vec<Position>>,
+    remaining: usize,
+    random: &mut Random,
+) -> Option<Vec<Vec<Position>>> {
+    let palindrome = word.len() > 1 && word.iter().eq(word.iter().rev());
+    for _ in 0..PLACEMENTS {
+        let direction = random.pick(&spec.directions);
+        let start = (random.below(spec.columns), random.below(spec.rows));
+
+        let mut path = vec![start];
+        for _ in 1..word.len() {
+            let last = *path.last().expect("paths start with a cell");
+            match cells.offset(last, direction) {
+                Some(next) => path.push(next),
+                None => break,
+            }
+        }
+        if path.len() < word.len() {
+            continue;
+        }
+
+        let fits = path
+            .iter()
+            .zip(word)
+            .all(|(cell, letter)| cells[*cell].is_none_or(|c| c == *letter));
+        let mut paths = vec![path.clone()];
+        if palindrome && spec.directions.contains(&(-direction.0, -direction.1)) {
+            paths.push(path.iter().rev().copied().collect());
+        }
+        if !fits || paths.len() > remaining || paths.iter().any(|p| taken.contains(p)) {
+            continue;
+        }
+
+        for (cell, letter) in path.iter().zip(word) {
+            cells[*cell] = Some(*letter);
+        }
+        return Some(paths);
+    }
+
+    None
+}
+
+// One try at a grid: hides the words, fills the rest, then refills the
+// free cells of unwanted occurrences until the counts are exact
+fn attempt(spec: &Spec, alphabet: &[char], random: &mut Random) -> Option<Grid<char>> {
+    let mut cells = Grid::filled(spec.columns, spec.rows, None);
+    let mut wanted: HashMap<String, HashSet<Vec<Position>>> = HashMap::new();
+    let mut taken = HashSet::new();
+
+    // longest words first, while the grid is emptiest
+    let mut order: Vec<&(String, usize)> = spec.words.iter().collect();
+    order.sort_by_key(|(word, _)| std::cmp::Reverse(word.chars().count()));
+    for (word, count) in order {
+        let letters: Vec<char> = word.chars().collect();
+        let mut placed = 0;
+        while placed < *count {
+            let paths = place(&letters, spec, &mut cells, &taken, count - placed, random)?;
+            placed += paths.len();
+            for path in paths {
+                taken.insert(path.clone());
+                wanted.entry(word.clone()).or_default().insert(path);
+            }
+        }
+    }
+
+    let locked: HashSet<Position> = taken.iter().flatten().copied().collect();
+    let mut grid = Grid::filled(spec.columns, spec.rows, ' ');
+    for (position, cell) in cells.iter() {
+        grid[position] = cell.unwrap_or_else(|| random.pick(alphabet));
+    }
+
+    let words: Vec<&str> = spec.words.iter().map(|(w, _)| w.as_str()).collect();
+    let dictionary = Dictionary::new(&words);
+    for _ in 0..REFILLS {
+        let found = occurrences(&grid, &dictionary, &spec.directions);
+        let unwanted: Vec<&Vec<Position>> = found
+            .iter()
+            .flat_map(|(word, sets)| {
+                let wanted = wanted.get(word);
+                sets.iter()
+                    .filter(move |set| wanted.is_none_or(|w| !w.contains(*set)))
+            })
+            .collect();
+        if unwanted.is_empty() {
+            return Some(grid);
+        }
+
+        let free: HashSet<Position> = unwanted
+            .into_iter()
+            .flatten()
+            .filter(|cell| !locked.contains(cell))
+            .copied()
+            .collect();
+        if free.is_empty() {
+            return None;
+        }
+
+        // refill in a fixed order to stay deterministic
+        let mut free: Vec<Position> = free.into_iter().collect();
+        free.sort_unstable();
+        for cell in free {
+            grid[cell] = random.pick(alphabet);
+        }
+    }
+
+    None
+}
+
+/// A word search holding every word of `spec` exactly the requested number
+/// of times along its directions, always the same for a given seed. The
+/// result is checked by searching it.
+pub fn generate(spec: &Spec) -> Result<Grid<char>, PuzzleError> {
+    let spec = spec.clone().validate()?;
+    let alphabet = spec.alphabet();
+    let mut random = Random(spec.seed);
+
+    for _ in 0..ATTEMPTS {
+        if let Some(grid) = attempt(&spec, &alphabet, &mut random) {
+            let words: Vec<String> = spec.words.iter().map(|(w, _)| w.clone()).collect();
+            let counts = count_occurrences(&grid, &words, &spec.directions);
+            let wanted: Vec<usize> = spec.words.iter().map(|(_, count)| *count).collect();
+            if counts != wanted {
+                return Err(PuzzleError::invalid(format!(
+                    "generated grid fails its self-check: found {counts:?} occurrences \
+                     instead of {wanted:?}"
+                )));
+            }
+
+            return Ok(grid);
+        }
+    }
+
+    Err(PuzzleError::invalid(
+        "could not hide every word the requested number of times, \
+         try another seed or a larger grid",
+    ))
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Day4;
+    use puzzle::Solution;
+
+    fn spec(words: &[(&str, usize)], seed: u64) -> Spec {
+        Spec {
+            columns: 12,
+            rows: 10,
+            words: words.iter().map(|(w, n)| (w.to_string(), *n)).collect(),
+            directions: ALL_DIRECTIONS.to_vec(),
+            alphabet: Vec::new(),
+            seed,
+        }
+    }
+
+    #[test]
+    fn generate_exact_counts() {
+        let spec = spec(&[("XMAS", 7), ("RUST", 2), ("LEVEL", 2)], 2024);
+        let grid = generate(&spec).unwrap();
+        assert_eq!((grid.columns(), grid.rows()), (12, 10));
+
+        let words = ["XMAS", "RUST", "LEVEL"].map(String::from);
+        assert_eq!(count_occurrences(&grid, &words, &ALL_DIRECTIONS), [7, 2, 2]);
+
+        // the search and the puzzle itself agree
+        let level = find_words(
+            &grid,
+            &Dictionary::new(["LEVEL"]),
+            &ALL_DIRECTIONS,
+            Walk::Straight,
+        );
+        assert_eq!(level.len(), 2);
+        let word_search = Day4::parse(&grid.to_string()).unwrap();
+        assert_eq!(Day4::part1(&word_search), 7);
+    }
+
+    #[test]
+    fn generate_palindromes() {
+        // read both ways along every direction, LEVEL occurs in pairs
+        let error = generate(&spec(&[("LEVEL", 1), ("XMAS", 2)], 1)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "'LEVEL' reads the same both ways, so it occurs an even number of times"
+        );
+
+        // but not when only read forwards
+        let forward = Spec {
+            directions: vec![(1, 0), (1, 1), (0, 1)],
+            ..spec(&[("LEVEL", 1), ("XMAS", 2)], 1)
+        };
+        let grid = generate(&forward).unwrap();
+        let words = ["LEVEL", "XMAS"].map(String::from);
+        assert_eq!(
+            count_occurrences(&grid, &words, &forward.directions),
+            [1, 2]
+        );
+
+        // single letters are counted once per cell
+        let grid = generate(&spec(&[("XMAS", 1), ("Q", 3)], 5)).unwrap();
+        let words = ["XMAS", "Q"].map(String::from);
+        assert_eq!(count_occurrences(&grid, &words, &ALL_DIRECTIONS), [1, 3]);
+    }
+
+    #[test]
+    fn generate_is_deterministic() {
+        let first = generate(&spec(&[("XMAS", 3)], 7)).unwrap();
+        assert_eq!(generate(&spec(&[("XMAS", 3)], 7)).unwrap(), first);
+        assert_ne!(generate(&spec(&[("XMAS", 3)], 8)).unwrap(), first);
+
+        // only the letters of the words fill the grid by default
+        assert!(first.iter().all(|(_, c)| "XMAS".contains(*c)));
+    }
+
+    #[test]
+    fn generate_options() {
+        let args: Vec<String> = [
+            "--words",
+            "XMAS:2,SANTA",
+            "--seed",
+            "3",
+            "--directions",
+            "forward",
+        ]
+        .map(String::from)
+        .to_vec();
+        let parsed = Spec::parse("8x6", &args).unwrap();
+        assert_eq!(
+            parsed.words,
+            [("XMAS".to_string(), 2), ("SANTA".to_string(), 1)]
+        );
+        assert_eq!(parsed.directions, [(1, 0), (1, 1), (0, 1)]);
+
+        let grid = generate(&parsed).unwrap();
+        let words = ["XMAS", "SANTA"].map(String::from);
+        assert_eq!(count_occurrences(&grid, &words, &parsed.directions), [2, 1]);
+
+        assert!(Spec::parse("8by6", &args).is_err());
+        let words = ["--words", "XMAS:2,SANTA:x"].map(String::from);
+        assert_eq!(
+            Spec::parse("8x6", &words).unwrap_err().to_string(),
+            "Invalid value for --words at line 1, column 14: invalid number 'x'"
+        );
+        assert_eq!(
+            Spec::parse("8xy", &args).unwrap_err().to_string(),
+            "Invalid value for --generate at line 1, column 3: invalid number 'y'"
+        );
+        assert!(Spec::parse("3x3", &args).is_err());
+        assert!(Spec::parse("8x6", &["--words".to_string(), "A,A".to_string()]).is_err());
+
+        // XMAS cannot occur once without MAS occurring too
+        assert!(generate(&spec(&[("XMAS", 1), ("MAS", 0)], 1)).is_err());
+    }
+}
@@ -1,487 +1,394 @@
-use csv::Reader;
-use serde::de::DeserializeOwned;
-use std::collections::HashMap;
-use std::collections::HashSet;
-use std::io::{self, Read};
-use std::{error::Error, fs, fs::File, process};
-
-#[derive(Debug)]
-pub struct Config {
-    puzzle_input: String,
-}
-
-impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
-        if args.len() < 2 {
-            return Err("Not enough arguments");
-        }
-
-        let puzzle_input = args[1].clone();
-
-        Ok(Config { puzzle_input })
-    }
+mod generate;
+mod packed;
+mod render;
+mod search;
+mod stencil;
+
+pub use generate::{count_occurrences, generate, Spec};
+pub use packed::{map_input, PackedGrid};
+pub use render::{Colouring, DIMMED};
+pub use search::{find_words, Dictionary, Direction, Match, Walk, ALL_DIRECTIONS, DIAGONALS};
+pub use stencil::{Orientations, Stencil, WILDCARD, X_MAS};
+
+use grid::Grid;
+use puzzle::{PuzzleError, Solution};
+use std::env;
+use std::fmt::Display;
+use std::fs;
+use std::hash::Hash;
+use std::io::{ErrorKind, Write};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// What to look for in the word search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Mode {
+    /// The answers of the puzzle.
+    Answers,
+    /// Every occurrence of some words, from `--find`.
+    Find(Find),
+    /// The placements of a stencil file, from `--stencil`.
+    Stencil {
+        file: String,
+        orientations: Orientations,
+    },
+    /// A new word search saved to the input file, or printed when it is
+    /// `-`, from `--generate`. An existing input file is only overwritten
+    /// with `--force`.
+    Generate { spec: Spec, force: bool },
+    /// The answers counted in place in the memory-mapped input, row by row
+    /// in parallel, from `--large`.
+    Large,
 }
 
-#[derive(Debug)]
-struct WordSearch {
-    lines: Vec<String>,
+/// What makes a single cell of the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Letters {
+    /// A Unicode scalar value.
+    Scalars,
+    /// A grapheme cluster, from `--graphemes`: a letter along with its
+    /// combining marks.
+    Graphemes,
 }
 
-#[derive(PartialEq, Eq, Hash, Debug)]
-struct CandidateWord {
-    letters: Vec<(usize, usize)>,
+/// How found words are shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum View {
+    /// One line per match.
+    List,
+    /// The grid with the matches highlighted, from `--render`.
+    Terminal,
+    /// The highlighted grid saved as an HTML page, from `--html <file>`.
+    Html(String),
 }
 
-fn get_lines(raw_input: &str) -> Vec<&str> {
-    let chunks: Vec<&str> = raw_input.trim().split("\n").collect();
-    chunks
+/// Words to find and how to show them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Find {
+    pub words: Vec<String>,
+    pub letters: Letters,
+    pub walk: Walk,
+    pub view: View,
+    pub colouring: Colouring,
 }
 
-impl WordSearch {
-    fn build(raw_content: &str) -> Result<WordSearch, &'static str> {
-        let lines: Vec<String> = raw_content
-            .trim()
-            .split("\n")
-            .map(|s| s.to_string())
-            .collect();
-
-        Ok(WordSearch { lines })
-    }
-
-    fn compute(&self, word: &str) -> u32 {
-        let key_letters = word.as_bytes();
-        let c = key_letters[0] as char;
-        let positions = self.get_positions(c);
-        let candidates = self.get_candidates(positions, word);
-
-        candidates.len().try_into().unwrap()
-    }
-
-    fn compute_cross_mas(&self, word: &str) -> u32 {
-        let key_letters = word.as_bytes();
-        let c = key_letters[0] as char;
-        let positions = self.get_positions(c);
-        let candidates = self.get_cross_candidates(positions, word);
-
-        let cross_total = self.compute_cross_total_from_candidates(candidates);
-        cross_total
-    }
-
-    fn compute_cross_total_from_candidates(&self, candidates: HashSet<CandidateWord>) -> u32 {
-        let mut cross_candidates = HashMap::new();
-
-        for c in &candidates {
-            assert_eq!(c.letters.len(), 3);
-
-            cross_candidates
-                .entry(c.letters[1])
-                .and_modify(|count| *count += 1)
-                .or_insert(1);
-        }
-
-        let mut total: u32 = 0;
-        for c in &cross_candidates {
-            assert!(*c.1 <= 2);
-
-            if *c.1 == 2 {
-                total += 1;
-            }
-        }
-
-        total
-    }
-
-    fn get_positions(&self, letter: char) -> Vec<(usize, usize)> {
-        let mut positions = Vec::new();
-        self.lines
-            .iter()
-            .enumerate()
-            .for_each(|(line_number, &ref line)| {
-                line.match_indices(letter)
-                    .for_each(|(column, &ref c)| positions.push((line_number, column)));
-            });
-
-        positions
-    }
-
-    fn get_candidates(&self, positions: Vec<(usize, usize)>, word: &str) -> HashSet<CandidateWord> {
-        let key_letters = word.as_bytes();
-        let length = word.len();
-        let num_lines = self.lines.len();
-
-        assert!(num_lines > 0);
-
-        let line_length = self.lines[0].len();
-
-        assert!(line_length > 0);
-
-        let max_decrement = length - 1;
-
-        let mut candidates = HashSet::new();
-        for pos in positions {
-            // we have up to 8 candidates
-
-            // upper vertical
-            if pos.0 >= max_decrement {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 - ix, pos.1);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
+impl Find {
+    fn parse(words: &str, options: &[String]) -> Result<Find, PuzzleError> {
+        let mut find = Find {
+            words: words.split(',').map(|w| w.trim().to_string()).collect(),
+            letters: Letters::Scalars,
+            walk: Walk::Straight,
+            view: View::List,
+            colouring: Colouring::PerWord,
+        };
+
+        let mut remaining = options.iter();
+        while let Some(option) = remaining.next() {
+            match option.as_str() {
+                "--graphemes" => find.letters = Letters::Graphemes,
+                "--wrap" => find.walk = Walk::Wraparound,
+                "--path" => find.walk = Walk::Path,
+                "--render" => find.view = View::Terminal,
+                "--html" | "--colour" => {
+                    let Some(value) = remaining.next() else {
+                        return Err(PuzzleError::usage(format!("Missing value after {option}")));
+                    };
+                    if option == "--html" {
+                        find.view = View::Html(value.clone());
                     } else {
-                        success = false;
-                        break;
+                        find.colouring = Colouring::parse(value)?;
                     }
                 }
-
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
-
-                    candidates.insert(candidate);
-                }
+                _ => return Err(PuzzleError::usage(USAGE)),
             }
+        }
 
-            // first diagonal
-            if pos.0 >= max_decrement && pos.1 <= line_length - length {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 - ix, pos.1 + ix);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+        Ok(find)
+    }
+}
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+const USAGE: &str = "Usage: day4 <input> [--find <word,...> [--graphemes] [--wrap | --path] \
+                     [--render | --html <file>] [--colour <word|match>] | \
+                     --stencil <file> [--orientations <fixed|rotations|all>] | \
+                     --generate <columns>x<rows> --words <word[:count],...> [--seed <n>] \
+                     [--directions <all|orthogonal|diagonal|forward>] [--alphabet <letters>] \
+                     [--force] | \
+                     --large]";
 
-                    candidates.insert(candidate);
-                }
-            }
+#[derive(Debug)]
+pub struct Config {
+    puzzle_input: String,
+    mode: Mode,
+}
 
-            // right horizontal
-            if pos.1 <= line_length - length {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0, pos.1 + ix);
-                    let c = key_letters[ix] as char;
-                    let r = self.get_letter(p);
-                    if c == r {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+impl Config {
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
+        if args.len() < 2 {
+            return Err(PuzzleError::usage("Not enough arguments"));
+        }
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+        let puzzle_input = args[1].clone();
 
-                    candidates.insert(candidate);
-                }
+        let mode = match &args[2..] {
+            [] => Mode::Answers,
+            [option, words, options @ ..] if option == "--find" => {
+                Mode::Find(Find::parse(words, options)?)
             }
-
-            // second diagonal
-            if pos.0 <= num_lines - length && pos.1 <= line_length - length {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 + ix, pos.1 + ix);
-                    let c = key_letters[ix] as char;
-                    let r = self.get_letter(p);
-                    if c == r {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
+            [option, file] if option == "--stencil" => Mode::Stencil {
+                file: file.clone(),
+                orientations: Orientations::Fixed,
+            },
+            [option, file, flag, orientations]
+                if option == "--stencil" && flag == "--orientations" =>
+            {
+                Mode::Stencil {
+                    file: file.clone(),
+                    orientations: Orientations::parse(orientations)?,
                 }
-
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
-
-                    candidates.insert(candidate);
+            }
+            [option, size, options @ ..] if option == "--generate" => {
+                // the only option without a value
+                let spec_options: Vec<String> = options
+                    .iter()
+                    .filter(|o| *o != "--force")
+                    .cloned()
+                    .collect();
+                Mode::Generate {
+                    spec: Spec::parse(size, &spec_options)?,
+                    force: spec_options.len() < options.len(),
                 }
             }
+            [option] if option == "--large" => Mode::Large,
+            _ => return Err(PuzzleError::usage(USAGE)),
+        };
 
-            // down vertical
-            if pos.0 <= num_lines - length {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 + ix, pos.1);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+        Ok(Config { puzzle_input, mode })
+    }
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+    pub fn mode(&self) -> &Mode {
+        &self.mode
+    }
+}
 
-                    candidates.insert(candidate);
-                }
-            }
+#[derive(Debug)]
+pub struct WordSearch {
+    grid: Grid<char>,
+}
 
-            // third diagonal
-            if pos.0 <= num_lines - length && pos.1 >= max_decrement {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 + ix, pos.1 - ix);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+impl WordSearch {
+    fn build(raw_content: &str) -> Result<WordSearch, PuzzleError> {
+        let grid = Grid::build(raw_content)?;
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+        Ok(WordSearch { grid })
+    }
 
-                    candidates.insert(candidate);
-                }
-            }
+    /// Every occurrence of the words of `dictionary` walking along
+    /// `directions`.
+    pub fn search(
+        &self,
+        dictionary: &Dictionary,
+        directions: &[Direction],
+        walk: Walk,
+    ) -> Vec<Match> {
+        find_words(&self.grid, dictionary, directions, walk)
+    }
 
-            // left horizontal
-            if pos.1 >= max_decrement {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0, pos.1 - ix);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+    fn compute(&self, word: &str) -> u32 {
+        let matches = self.search(&Dictionary::new([word]), &ALL_DIRECTIONS, Walk::Straight);
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+        matches.len().try_into().unwrap()
+    }
 
-                    candidates.insert(candidate);
-                }
-            }
+    /// Number of placements of `stencil` in the grid.
+    pub fn count_stencil(&self, stencil: &Stencil, orientations: Orientations) -> usize {
+        stencil.count(&self.grid, orientations)
+    }
+}
 
-            // fourth diagonal
-            if pos.0 >= max_decrement && pos.1 >= max_decrement {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 - ix, pos.1 - ix);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+pub struct Day4;
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+impl Solution for Day4 {
+    type Input = WordSearch;
+    type Answer = u32;
 
-                    candidates.insert(candidate);
-                }
-            }
-        }
+    fn parse(raw_input: &str) -> Result<WordSearch, PuzzleError> {
+        WordSearch::build(raw_input)
+    }
 
-        candidates
+    fn part1(input: &WordSearch) -> u32 {
+        input.compute("XMAS")
     }
 
-    fn get_cross_candidates(
-        &self,
-        positions: Vec<(usize, usize)>,
-        word: &str,
-    ) -> HashSet<CandidateWord> {
-        let key_letters = word.as_bytes();
-        let length = word.len();
-        let num_lines = self.lines.len();
-
-        assert!(num_lines > 0);
-
-        let line_length = self.lines[0].len();
-
-        assert!(line_length > 0);
-
-        let max_decrement = length - 1;
-
-        let mut candidates = HashSet::new();
-        for pos in positions {
-            // we have up to 4 candidates
-
-            // first diagonal
-            if pos.0 >= max_decrement && pos.1 <= line_length - length {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 - ix, pos.1 + ix);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+    fn part2(input: &WordSearch) -> u32 {
+        let x_mas = Stencil::parse(X_MAS).expect("the X-MAS stencil is valid");
+        input
+            .count_stencil(&x_mas, Orientations::Rotations)
+            .try_into()
+            .unwrap()
+    }
+}
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+#[cfg(test)]
+fn compute_total_xmas(raw_data: &str) -> Result<u32, PuzzleError> {
+    let word_search = Day4::parse(raw_data)?;
+    Ok(Day4::part1(&word_search))
+}
 
-                    candidates.insert(candidate);
-                }
-            }
+#[cfg(test)]
+fn compute_total_xmas_part2(raw_data: &str) -> Result<u32, PuzzleError> {
+    let word_search = Day4::parse(raw_data)?;
+    Ok(Day4::part2(&word_search))
+}
 
-            // second diagonal
-            if pos.0 <= num_lines - length && pos.1 <= line_length - length {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 + ix, pos.1 + ix);
-                    let c = key_letters[ix] as char;
-                    let r = self.get_letter(p);
-                    if c == r {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+pub fn run(config: Config) -> Result<(u32, u32), PuzzleError> {
+    let content = puzzle::read_input(&config.puzzle_input)?;
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+    Day4::solve(&content).map_err(|e| e.in_file(&config.puzzle_input))
+}
 
-                    candidates.insert(candidate);
-                }
-            }
+/// The answers of an ASCII word search too large to be read whole, counted
+/// without a copy of its cells.
+pub fn run_large(config: &Config) -> Result<(u64, u64), PuzzleError> {
+    let input = map_input(&config.puzzle_input)?;
+    let grid = PackedGrid::parse(&input).map_err(|e| e.in_file(&config.puzzle_input))?;
 
-            // third diagonal
-            if pos.0 <= num_lines - length && pos.1 >= max_decrement {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 + ix, pos.1 - ix);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+    Ok((grid.count_word("XMAS"), grid.count_x_mas()))
+}
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+/// Words found with `--find`, along with the highlighted grid when
+/// rendered for the terminal, plain when `NO_COLOR` is set.
+#[derive(Debug)]
+pub struct Found {
+    pub matches: Vec<Match>,
+    pub rendered: Option<String>,
+}
 
-                    candidates.insert(candidate);
-                }
-            }
+fn find_in<L: Display + Eq + Hash>(
+    grid: &Grid<L>,
+    dictionary: &Dictionary<L>,
+    find: &Find,
+) -> Result<Found, PuzzleError> {
+    let matches = find_words(grid, dictionary, &ALL_DIRECTIONS, find.walk);
+
+    let rendered = match &find.view {
+        View::List => None,
+        // without colours, as asked by https://no-color.org
+        View::Terminal if env::var_os("NO_COLOR").is_some() => Some(render::plain(grid, &matches)),
+        View::Terminal => Some(render::ansi(grid, &matches, find.colouring)),
+        View::Html(file) => {
+            let title = format!("Day 4: {}", find.words.join(", "));
+            let page = render::html(grid, &matches, find.colouring, &title);
+            fs::write(file, page).map_err(|source| PuzzleError::Io {
+                file: file.clone(),
+                source,
+            })?;
+            None
+        }
+    };
 
-            // fourth diagonal
-            if pos.0 >= max_decrement && pos.1 >= max_decrement {
-                let mut letter_pos = Vec::new();
-                let mut success = true;
-                for ix in 0..length {
-                    let p = (pos.0 - ix, pos.1 - ix);
-                    let c = key_letters[ix] as char;
-                    if c == self.get_letter(p) {
-                        letter_pos.push(p);
-                    } else {
-                        success = false;
-                        break;
-                    }
-                }
+    Ok(Found { matches, rendered })
+}
 
-                if success {
-                    let candidate = CandidateWord {
-                        letters: letter_pos,
-                    };
+/// Every occurrence of the words of `find` with steps in any of the eight
+/// directions, with positions counted in its letters. The highlighted grid
+/// is saved when asked for as HTML.
+pub fn run_find(config: &Config, find: &Find) -> Result<Found, PuzzleError> {
+    let content = puzzle::read_input(&config.puzzle_input)?;
 
-                    candidates.insert(candidate);
-                }
-            }
+    match find.letters {
+        Letters::Scalars => {
+            let grid = Grid::build(&content).map_err(|e| e.in_file(&config.puzzle_input))?;
+            find_in(&grid, &Dictionary::new(&find.words), find)
+        }
+        Letters::Graphemes => {
+            let grid = Grid::parse_cells(&content, |line| line.graphemes(true).map(String::from))
+                .map_err(|e| e.in_file(&config.puzzle_input))?;
+            find_in(&grid, &Dictionary::graphemes(&find.words), find)
         }
-
-        candidates
     }
+}
 
-    fn get_letter(&self, position: (usize, usize)) -> char {
-        let num_lines = self.lines.len();
-        let line_length = self.lines[0].len();
-        assert!(position.0 < num_lines, "x = {}", position.0);
-        assert!(position.1 < line_length, "y = {}", position.1);
-
-        self.lines[position.0].as_bytes()[position.1] as char
-    }
+/// Number of placements of the stencil in `file`.
+pub fn run_stencil(
+    config: &Config,
+    file: &str,
+    orientations: Orientations,
+) -> Result<usize, PuzzleError> {
+    let stencil = Stencil::load(file)?;
+    let content = puzzle::read_input(&config.puzzle_input)?;
+    let word_search = Day4::parse(&content).map_err(|e| e.in_file(&config.puzzle_input))?;
+
+    Ok(word_search.count_stencil(&stencil, orientations))
 }
 
-fn deserialize<T, R>(reader: R) -> Result<Vec<T>, Box<dyn std::error::Error>>
-where
-    T: std::fmt::Debug + DeserializeOwned,
-    R: Read,
-{
-    let mut rdr = Reader::from_reader(reader);
-    let mut structs: Vec<T> = Vec::new();
-    for result in rdr.deserialize() {
-        let record: T = result?;
-        structs.push(record);
+/// Generates a word search from `spec`, then saves it to the input file
+/// unless it is `-`. The file must not exist yet unless `force` is set.
+pub fn run_generate(config: &Config, spec: &Spec, force: bool) -> Result<Grid<char>, PuzzleError> {
+    let grid = generate(spec)?;
+    if config.puzzle_input == "-" {
+        return Ok(grid);
     }
 
-    Ok(structs)
+    let file = &config.puzzle_input;
+    let saved = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .create_new(!force)
+        .open(file)
+        .and_then(|mut opened| opened.write_all(format!("{grid}\n").as_bytes()));
+
+    match saved {
+        Ok(()) => Ok(grid),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Err(PuzzleError::usage(format!(
+            "{file} already exists, add --force to overwrite it"
+        ))),
+        Err(source) => Err(PuzzleError::Io {
+            file: file.clone(),
+            source,
+        }),
+    }
 }
 
-fn compute_total_xmas(raw_data: &str) -> u32 {
-    let word_search = WordSearch::build(raw_data).unwrap();
-    word_search.compute("XMAS")
-}
+/// The rows of a generated word search as CSV, under a `row` header.
+pub fn rows_csv(grid: &Grid<char>) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(["row"]).expect("writing to memory");
+    for row in grid.iter_rows() {
+        writer
+            .write_record([row.iter().collect::<String>()])
+            .expect("writing to memory");
+    }
 
-fn compute_total_xmas_part2(raw_data: &str) -> u32 {
-    let word_search = WordSearch::build(raw_data).unwrap();
-    word_search.compute_cross_mas("MAS")
+    let bytes = writer.into_inner().expect("writing to memory");
+    String::from_utf8_lossy(&bytes).into_owned()
 }
 
-pub fn run(config: Config) -> Result<(u32, u32), Box<dyn Error>> {
-    let content = fs::read_to_string(config.puzzle_input)?;
-    let total = compute_total_xmas(&content);
-    let total_cross_mas = compute_total_xmas_part2(&content);
+/// Matches as CSV, one row per match with its start, its direction if
+/// straight and its cells as `x:y`.
+pub fn matches_csv(matches: &[Match]) -> String {
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer
+        .write_record(["word", "x", "y", "dx", "dy", "cells"])
+        .expect("writing to memory");
+    for m in matches {
+        writer
+            .write_record([
+                m.word.clone(),
+                m.start.0.to_string(),
+                m.start.1.to_string(),
+                m.direction.map(|d| d.0.to_string()).unwrap_or_default(),
+                m.direction.map(|d| d.1.to_string()).unwrap_or_default(),
+                m.cells
+                    .iter()
+                    .map(|(x, y)| format!("{x}:{y}"))
+                    .collect::<Vec<_>>()
+                    .join(" "),
+            ])
+            .expect("writing to memory");
+    }
 
-    Ok((total, total_cross_mas))
+    let bytes = writer.into_inner().expect("writing to memory");
+    String::from_utf8_lossy(&bytes).into_owned()
 }
 
 // Note on printing during tests:
@@ -490,9 +397,8 @@ pub fn run(config: Config) -> Result<(u32, u32), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use io::BufReader;
-
     use super::*;
+    use grid::Position;
 
     #[test]
     fn part1_simplified_logic_test() {
@@ -502,7 +408,7 @@ mod tests {
 .A..A.
 XMAS.S
 .X....";
-        assert_eq!(compute_total_xmas(data), 4);
+        assert_eq!(compute_total_xmas(data).unwrap(), 4);
     }
 
     #[test]
@@ -519,7 +425,45 @@ SAXAMASAAA
 MAMMMXMMMM
 MXMXAXMASX";
 
-        assert_eq!(compute_total_xmas(data), 18);
+        assert_eq!(compute_total_xmas(data).unwrap(), 18);
+    }
+
+    #[test]
+    fn part1_illustration() {
+        let data = "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+        let grid = Grid::build(data).unwrap();
+        let matches = find_words(
+            &grid,
+            &Dictionary::new(["XMAS"]),
+            &ALL_DIRECTIONS,
+            Walk::Straight,
+        );
+        assert_eq!(
+            render::plain(&grid, &matches),
+            "\
+....XXMAS.
+.SAMXMS...
+...S..A...
+..A.A.MS.X
+XMASAMX.MM
+X.....XA.A
+S.S.S.S.SS
+.A.A.A.A.A
+..M.M.M.MM
+.X.X.XMASX
+"
+        );
     }
 
     #[test]
@@ -536,6 +480,172 @@ S.S.S.S.S.
 M.M.M.M.M.
 ..........";
 
-        assert_eq!(compute_total_xmas_part2(data), 9);
+        assert_eq!(compute_total_xmas_part2(data).unwrap(), 9);
+    }
+
+    #[test]
+    fn part2_as_stencil_options() {
+        let config = Config::build(
+            &[
+                "day4",
+                "in.txt",
+                "--stencil",
+                "x.txt",
+                "--orientations",
+                "all",
+            ]
+            .map(String::from),
+        );
+        assert_eq!(
+            *config.unwrap().mode(),
+            Mode::Stencil {
+                file: "x.txt".to_string(),
+                orientations: Orientations::All
+            }
+        );
+        assert!(Config::build(
+            &[
+                "day4",
+                "in.txt",
+                "--stencil",
+                "x.txt",
+                "--orientations",
+                "up"
+            ]
+            .map(String::from)
+        )
+        .is_err());
+
+        // the X-MAS mirror images are rotations of it
+        let word_search = Day4::parse("M.S\n.A.\nM.S").unwrap();
+        let x_mas = Stencil::parse(X_MAS).unwrap();
+        assert_eq!(word_search.count_stencil(&x_mas, Orientations::All), 1);
+    }
+
+    #[test]
+    fn large_option() {
+        let config = Config::build(&["day4", "in.txt", "--large"].map(String::from));
+        assert_eq!(*config.unwrap().mode(), Mode::Large);
+        assert!(Config::build(&["day4", "in.txt", "--large", "x"].map(String::from)).is_err());
+    }
+
+    #[test]
+    fn generate_refuses_to_overwrite() {
+        let file = env::temp_dir().join(format!("day4-generate-{}.txt", std::process::id()));
+        let file = file.to_str().unwrap();
+        let args = |force: &[&str]| {
+            let mut args = ["day4", file, "--generate", "6x6", "--words", "XMAS"]
+                .map(String::from)
+                .to_vec();
+            args.extend(force.iter().map(|f| f.to_string()));
+            Config::build(&args).unwrap()
+        };
+
+        let config = args(&["--force"]);
+        let Mode::Generate { spec, force } = config.mode() else {
+            panic!("{config:?}");
+        };
+        assert!(force);
+        assert_eq!(spec.words, [("XMAS".to_string(), 1)]);
+
+        fs::write(file, "kept\n").unwrap();
+        let config = args(&[]);
+        let Mode::Generate { spec, force } = config.mode() else {
+            panic!("{config:?}");
+        };
+        let error = run_generate(&config, spec, *force).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            format!("{file} already exists, add --force to overwrite it")
+        );
+        assert_eq!(fs::read_to_string(file).unwrap(), "kept\n");
+
+        let grid = run_generate(&config, spec, true).unwrap();
+        assert_eq!(fs::read_to_string(file).unwrap(), format!("{grid}\n"));
+        fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn rows_as_csv() {
+        let grid = Grid::build("X,M\n\"AS").unwrap();
+        assert_eq!(rows_csv(&grid), "row\n\"X,M\"\n\"\"\"AS\"\n");
+    }
+
+    #[test]
+    fn find_words_with_positions() {
+        let data = "\
+..X...
+.SAMX.
+.A..A.
+XMAS.S
+.X....";
+        let word_search = Day4::parse(data).unwrap();
+        let matches = word_search.search(
+            &Dictionary::new(["XMAS", "SAMX"]),
+            &ALL_DIRECTIONS,
+            Walk::Straight,
+        );
+        let found: Vec<(&str, Position, Option<Direction>)> = matches
+            .iter()
+            .map(|m| (m.word.as_str(), m.start, m.direction))
+            .collect();
+
+        assert_eq!(
+            found,
+            [
+                ("XMAS", (2, 0), Some((1, 1))),
+                ("SAMX", (1, 1), Some((1, 0))),
+                ("SAMX", (1, 1), Some((0, 1))),
+                ("XMAS", (4, 1), Some((-1, 0))),
+                ("XMAS", (0, 3), Some((1, 0))),
+                ("SAMX", (3, 3), Some((-1, 0))),
+                ("SAMX", (5, 3), Some((-1, -1))),
+                ("XMAS", (1, 4), Some((0, -1))),
+            ]
+        );
+        assert_eq!(matches[7].cells, [(1, 4), (1, 3), (1, 2), (1, 1)]);
+
+        let config = Config::build(&["day4", "in.txt", "--find", "XMAS, SAMX"].map(String::from));
+        assert_eq!(
+            *config.unwrap().mode(),
+            Mode::Find(Find {
+                words: vec!["XMAS".to_string(), "SAMX".to_string()],
+                letters: Letters::Scalars,
+                walk: Walk::Straight,
+                view: View::List,
+                colouring: Colouring::PerWord,
+            })
+        );
+
+        let config = Config::build(
+            &[
+                "day4",
+                "in.txt",
+                "--find",
+                "é",
+                "--graphemes",
+                "--path",
+                "--html",
+                "out.html",
+                "--colour",
+                "match",
+            ]
+            .map(String::from),
+        );
+        let Mode::Find(find) = config.unwrap().mode().clone() else {
+            panic!("--find is a find mode");
+        };
+        assert_eq!(find.letters, Letters::Graphemes);
+        assert_eq!(find.walk, Walk::Path);
+        assert_eq!(find.view, View::Html("out.html".to_string()));
+        assert_eq!(find.colouring, Colouring::PerMatch);
+
+        assert!(
+            Config::build(&["day4", "in.txt", "--find", "X", "--html"].map(String::from)).is_err()
+        );
+        assert!(
+            Config::build(&["day4", "in.txt", "--find", "X", "--bold"].map(String::from)).is_err()
+        );
+        assert!(Config::build(&["day4", "in.txt", "--find"].map(String::from)).is_err());
     }
 }
@@ -1,13 +1,107 @@
-use std::{env, error};
+use day4::{Mode, View};
+use puzzle::output::{Format, Output};
+use std::env;
+use std::time::Instant;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    println!("Hello, day 4!");
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let output = Output::from_args(&mut args);
+    let input = args.get(1).cloned();
 
-    let args: Vec<String> = env::args().collect();
-    let config = day4::Config::build(&args)?;
-    let (total, total_cross_mas) = day4::run(config)?;
+    let config = day4::Config::build(&args)
+        .unwrap_or_else(|err| output.fail(4, input.as_deref(), "Problem parsing arguments", &err));
 
-    println!("Num total xmas:         {total}");
-    println!("Num total cross-mas:    {total_cross_mas}");
-    Ok(())
+    // a grid generated to stdout must read back as a word search
+    let grid_to_stdout =
+        matches!(config.mode(), Mode::Generate { .. }) && input.as_deref() == Some("-");
+    if output.is_text() && !grid_to_stdout {
+        println!("Hello, day 4!");
+    }
+
+    match config.mode() {
+        Mode::Answers | Mode::Large => (),
+        Mode::Find(find) => {
+            let found = day4::run_find(&config, find)
+                .unwrap_or_else(|err| output.fail(4, input.as_deref(), "Application error", &err));
+            let matches = found.matches;
+            match output.format() {
+                Format::Text => {
+                    match (&found.rendered, &find.view) {
+                        (Some(rendered), _) => print!("{rendered}"),
+                        (None, View::Html(file)) => println!("Saved highlighted grid to {file}"),
+                        (None, _) => {
+                            for m in &matches {
+                                match m.direction {
+                                    Some(direction) => {
+                                        println!("{} at {:?} going {direction:?}", m.word, m.start)
+                                    }
+                                    None => println!("{} along {:?}", m.word, m.cells),
+                                }
+                            }
+                        }
+                    }
+                    println!("Num matches: {}", matches.len());
+                }
+                Format::Json => matches.iter().for_each(|m| {
+                    println!(
+                        "{}",
+                        serde_json::to_string(m).expect("matches are plain data")
+                    )
+                }),
+                Format::Csv => print!("{}", day4::matches_csv(&matches)),
+            }
+            return;
+        }
+        Mode::Generate { spec, force } => {
+            let grid = day4::run_generate(&config, spec, *force)
+                .unwrap_or_else(|err| output.fail(4, input.as_deref(), "Application error", &err));
+            match output.format() {
+                Format::Text if input.as_deref() == Some("-") => println!("{grid}"),
+                Format::Text => println!(
+                    "Saved {}x{} word search to {}",
+                    grid.columns(),
+                    grid.rows(),
+                    input.unwrap_or_default()
+                ),
+                Format::Json => {
+                    let rows: Vec<String> =
+                        grid.iter_rows().map(|row| row.iter().collect()).collect();
+                    println!("{}", serde_json::json!({ "rows": rows }))
+                }
+                Format::Csv => print!("{}", day4::rows_csv(&grid)),
+            }
+            return;
+        }
+        Mode::Stencil { file, orientations } => {
+            let placements = day4::run_stencil(&config, file, *orientations)
+                .unwrap_or_else(|err| output.fail(4, input.as_deref(), "Application error", &err));
+            match output.format() {
+                Format::Text => println!("Num placements: {placements}"),
+                Format::Json => println!("{}", serde_json::json!({ "placements": placements })),
+                Format::Csv => print!("placements\n{placements}\n"),
+            }
+            return;
+        }
+    }
+
+    let start = Instant::now();
+    let answers = match config.mode() {
+        Mode::Large => day4::run_large(&config),
+        _ => day4::run(config).map(|(total, cross)| (total.into(), cross.into())),
+    };
+    let (total, total_cross_mas) =
+        answers.unwrap_or_else(|err| output.fail(4, input.as_deref(), "Application error", &err));
+    let elapsed = start.elapsed();
+
+    if output.is_text() {
+        println!("Num total xmas:         {total}");
+        println!("Num total cross-mas:    {total_cross_mas}");
+    } else {
+        output.answers(
+            4,
+            input.as_deref().unwrap_or_default(),
+            &[&total, &total_cross_mas],
+            elapsed,
+        );
+    }
 }
@@ -0,0 +1,315 @@
+use crate::{Direction, ALL_DIRECTIONS};
+use grid::Position;
+use memmap2::Mmap;
+use puzzle::PuzzleError;
+use rayon::prelude::*;
+use std::fs::File;
+
+/// Maps `file` into memory, so that grids larger than the memory are paged
+/// in as they are scanned instead of being read whole.
+pub fn map_input(file: &str) -> Result<Mmap, PuzzleError> {
+    let io = |source: std::io::Error| PuzzleError::Io {
+        file: file.to_string(),
+        source,
+    };
+    let opened = File::open(file).map_err(io)?;
+
+    // SAFETY: the map is only ever read. Truncating the input while the
+    // puzzle runs would be undefined behaviour, as it is for any program
+    // mapping its input, and nothing here writes to it.
+    unsafe { Mmap::map(&opened) }.map_err(io)
+}
+
+/// A word search of ASCII letters searched in place in the bytes of its
+/// input, one byte per cell, with rows scanned in parallel.
+///
+/// Unlike [`Grid`](grid::Grid) it holds no copy of the cells, so that it
+/// works on memory-mapped inputs far larger than the memory.
+#[derive(Debug, Clone, Copy)]
+pub struct PackedGrid<'a> {
+    bytes: &'a [u8],
+    columns: usize,
+    rows: usize,
+    // distance between the starts of two rows, line ending included
+    stride: usize,
+}
+
+impl<'a> PackedGrid<'a> {
+    /// Views `raw_data` as a grid, with the same rules as
+    /// [`Grid::parse`](grid::Grid::parse): `\n` or `\r\n` line endings, used
+    /// throughout, blank or whitespace-only lines before and after the grid
+    /// ignored and rows all of the same length. Cells must be ASCII.
+    pub fn parse(raw_data: &'a [u8]) -> Result<PackedGrid<'a>, PuzzleError> {
+        let first = raw_data.iter().position(|b| !b.is_ascii_whitespace());
+        let last = raw_data.iter().rposition(|b| !b.is_ascii_whitespace());
+
+        let (Some(first), Some(last)) = (first, last) else {
+            return Err(PuzzleError::invalid("no rows to parse"));
+        };
+
+        // whole lines, from the start of the first one with a cell to the
+        // end of the last one
+        let first = raw_data[..first]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |n| n + 1);
+        let last = raw_data[last..]
+            .iter()
+            .position(|&b| b == b'\n')
+            .map_or(raw_data.len(), |n| last + n);
+        let bytes = &raw_data[first..last];
+        let bytes = bytes.strip_suffix(b"\r").unwrap_or(bytes);
+
+        let first_line = raw_data[..first].iter().filter(|&&b| b == b'\n').count() + 1;
+
+        let line_end = bytes.iter().position(|&b| b == b'\n');
+        let (columns, ending): (usize, &[u8]) = match line_end {
+            Some(end) if bytes[end - 1] == b'\r' => (end - 1, b"\r\n"),
+            Some(end) => (end, b"\n"),
+            None => (bytes.len(), b"\n"),
+        };
+        let stride = columns + ending.len();
+
+        let grid = PackedGrid {
+            bytes,
+            columns,
+            rows: (bytes.len() + ending.len()).div_ceil(stride),
+            stride,
+        };
+
+        // the first error in the input rather than any of them
+        let error = (0..grid.rows)
+            .into_par_iter()
+            .find_map_first(|y| grid.check_row(y, ending).err());
+
+        match error {
+            Some((y, column, description)) => {
+                Err(PuzzleError::at(first_line + y, column + 1, description))
+            }
+            None => Ok(grid),
+        }
+    }
+
+    // Zero-based row and column of what is wrong with row `y`, if anything
+    fn check_row(&self, y: usize, ending: &[u8]) -> Result<(), (usize, usize, String)> {
+        let rest = &self.bytes[(y * self.stride).min(self.bytes.len())..];
+        let line = rest.split(|&b| b == b'\n').next().unwrap_or_default();
+        let line = line.strip_suffix(b"\r").unwrap_or(line);
+
+        if line.len() != self.columns {
+            return Err((
+                y,
+                line.len().min(self.columns),
+                format!(
+                    "expected {} cells per row, found {}",
+                    self.columns,
+                    line.len()
+                ),
+            ));
+        }
+
+        if let Some(x) = line.iter().position(|&b| !b.is_ascii() || b == b'\r') {
+            return Err((y, x, format!("unexpected byte 0x{:02x}", line[x])));
+        }
+
+        let after = &rest[self.columns..];
+        if !after.is_empty() && !after.starts_with(ending) {
+            return Err((y, self.columns, "mixed line endings".to_string()));
+        }
+
+        Ok(())
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    fn row(&self, y: usize) -> &'a [u8] {
+        let start = y * self.stride;
+        &self.bytes[start..start + self.columns]
+    }
+
+    // Whether `rest` is read from the cell after `(x, y)` along `direction`
+    fn reads(&self, rest: &[u8], (x, y): Position, (dx, dy): Direction) -> bool {
+        let length = rest.len() as isize;
+        let last_x = x.checked_add_signed(dx * length);
+        let last_y = y.checked_add_signed(dy * length);
+        match (last_x, last_y) {
+            (Some(last_x), Some(last_y)) if last_x < self.columns && last_y < self.rows => {}
+            _ => return false,
+        }
+
+        // every cell lies between the first and the last one
+        rest.iter().zip(1..).all(|(&letter, step)| {
+            let cell_x = x.wrapping_add_signed(dx * step);
+            let cell_y = y.wrapping_add_signed(dy * step);
+            self.bytes[cell_y * self.stride + cell_x] == letter
+        })
+    }
+
+    /// Number of occurrences of the ASCII `word` going in any of the eight
+    /// directions, a single letter being counted once per cell.
+    pub fn count_word(&self, word: &str) -> u64 {
+        let Some((&first, rest)) = word.as_bytes().split_first() else {
+            return 0;
+        };
+
+        (0..self.rows)
+            .into_par_iter()
+            .map(|y| {
+                let starts = self.row(y).iter().enumerate().filter(|(_, &b)| b == first);
+                let mut count = 0;
+                for (x, _) in starts {
+                    if rest.is_empty() {
+                        count += 1;
+                    } else {
+                        count += ALL_DIRECTIONS
+                            .iter()
+                            .filter(|&&direction| self.reads(rest, (x, y), direction))
+                            .count() as u64;
+                    }
+                }
+                count
+            })
+            .sum()
+    }
+
+    /// Number of X-MAS, as counted by the [`X_MAS`](crate::X_MAS) stencil
+    /// and its rotations: an `A` with a "MAS" read either way along both of
+    /// its diagonals.
+    pub fn count_x_mas(&self) -> u64 {
+        if self.columns < 3 || self.rows < 3 {
+            return 0;
+        }
+
+        let crossed = |a: u8, b: u8| matches!((a, b), (b'M', b'S') | (b'S', b'M'));
+
+        (1..self.rows - 1)
+            .into_par_iter()
+            .map(|y| {
+                let (above, row, below) = (self.row(y - 1), self.row(y), self.row(y + 1));
+                (1..self.columns - 1)
+                    .filter(|&x| {
+                        row[x] == b'A'
+                            && crossed(above[x - 1], below[x + 1])
+                            && crossed(above[x + 1], below[x - 1])
+                    })
+                    .count() as u64
+            })
+            .sum()
+    }
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{generate, Day4, Spec};
+    use puzzle::Solution;
+
+    const SAMPLE: &str = "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX";
+
+    #[test]
+    fn packed_counts_match_samples() {
+        let grid = PackedGrid::parse(SAMPLE.as_bytes()).unwrap();
+        assert_eq!((grid.columns(), grid.rows()), (10, 10));
+        assert_eq!(grid.count_word("XMAS"), 18);
+        assert_eq!(grid.count_x_mas(), 9);
+        assert_eq!(grid.count_word("X"), 19);
+
+        let simplified = "..X...\n.SAMX.\n.A..A.\nXMAS.S\n.X....\n";
+        let grid = PackedGrid::parse(simplified.as_bytes()).unwrap();
+        assert_eq!(grid.count_word("XMAS"), 4);
+
+        let windows = format!("\r\n{}\r\n\r\n", SAMPLE.replace('\n', "\r\n"));
+        let grid = PackedGrid::parse(windows.as_bytes()).unwrap();
+        assert_eq!((grid.columns(), grid.rows()), (10, 10));
+        assert_eq!((grid.count_word("XMAS"), grid.count_x_mas()), (18, 9));
+    }
+
+    #[test]
+    fn packed_edge_lines_match_grid() {
+        let padded = " \t\n\r\n XMAS\nSAMX \n  \n\t";
+        let grid = PackedGrid::parse(padded.as_bytes()).unwrap();
+        let expected = grid::Grid::build(padded).unwrap();
+        assert_eq!((grid.columns(), grid.rows()), (5, 2));
+        assert_eq!((expected.columns(), expected.rows()), (5, 2));
+        assert_eq!(grid.row(0), b" XMAS");
+        assert_eq!(grid.count_word("XMAS"), 2);
+
+        let short = PackedGrid::parse(b"  \r\nXMAS\r\nXMA\r\n \r\n").unwrap_err();
+        assert_eq!(
+            short.to_string(),
+            "line 3, column 4: expected 4 cells per row, found 3"
+        );
+    }
+
+    #[test]
+    fn packed_counts_match_generated_grids() {
+        for seed in 0..20 {
+            let spec = Spec {
+                columns: 9 + seed as usize % 5,
+                rows: 7 + seed as usize % 4,
+                words: vec![("XMAS".to_string(), seed as usize % 6)],
+                directions: ALL_DIRECTIONS.to_vec(),
+                alphabet: "XMAS".chars().collect(),
+                seed,
+            };
+            let raw_data = format!("{}\n", generate(&spec).unwrap());
+
+            let packed = PackedGrid::parse(raw_data.as_bytes()).unwrap();
+            let word_search = Day4::parse(&raw_data).unwrap();
+            assert_eq!(
+                (packed.count_word("XMAS"), packed.count_x_mas()),
+                (
+                    u64::from(Day4::part1(&word_search)),
+                    u64::from(Day4::part2(&word_search))
+                ),
+                "seed {seed}"
+            );
+        }
+    }
+
+    #[test]
+    fn packed_input_errors() {
+        let short = PackedGrid::parse(b"\nXMAS\nXMA\nXMAS").unwrap_err();
+        assert_eq!(
+            short.to_string(),
+            "line 3, column 4: expected 4 cells per row, found 3"
+        );
+
+        let split = PackedGrid::parse(b"XMAS\nX\nMA\nXMAS").unwrap_err();
+        assert_eq!(
+            split.to_string(),
+            "line 2, column 2: expected 4 cells per row, found 1"
+        );
+
+        let mixed = PackedGrid::parse(b"XMAS\r\nXMAS\nXMAS").unwrap_err();
+        assert_eq!(mixed.to_string(), "line 2, column 5: mixed line endings");
+
+        let accented = PackedGrid::parse("XMAS\nXÄS".as_bytes()).unwrap_err();
+        assert_eq!(
+            accented.to_string(),
+            "line 2, column 2: unexpected byte 0xc3"
+        );
+
+        assert!(PackedGrid::parse(b"\n\r\n").is_err());
+    }
+}
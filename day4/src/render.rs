@@ -0,0 +1,225 @@
+use crate::Match;
+use grid::{Grid, Position};
+use puzzle::PuzzleError;
+use std::collections::HashMap;
+use std::fmt::Display;
+
+/// Shown instead of the letters outside every match, as in the puzzle.
+pub const DIMMED: char = '.';
+
+/// How matches are told apart when rendered.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colouring {
+    /// Every occurrence of a word has the colour of the word.
+    PerWord,
+    /// Every match has its own colour, cycling through the palette.
+    PerMatch,
+}
+
+impl Colouring {
+    pub(crate) fn parse(value: &str) -> Result<Colouring, PuzzleError> {
+        match value {
+            "word" => Ok(Colouring::PerWord),
+            "match" => Ok(Colouring::PerMatch),
+            _ => Err(PuzzleError::usage(format!(
+                "Invalid colouring '{value}': must be word or match"
+            ))),
+        }
+    }
+}
+
+// ANSI foreground codes and the matching HTML colours
+const PALETTE: [(u8, &str); 6] = [
+    (31, "#e06c75"),
+    (32, "#98c379"),
+    (33, "#e5c07b"),
+    (34, "#61afef"),
+    (35, "#c678dd"),
+    (36, "#56b6c2"),
+];
+
+// Colour of every word, in order of first match
+fn word_colours(matches: &[Match]) -> Vec<(&str, usize)> {
+    let mut words: Vec<(&str, usize)> = Vec::new();
+    for m in matches {
+        if words.iter().all(|(word, _)| *word != m.word) {
+            words.push((&m.word, words.len() % PALETTE.len()));
+        }
+    }
+    words
+}
+
+// Colour of every matched cell. Where matches cross, the first one wins.
+fn paint(matches: &[Match], colouring: Colouring) -> HashMap<Position, usize> {
+    let words = word_colours(matches);
+    let mut colours = HashMap::new();
+    for (index, m) in matches.iter().enumerate() {
+        let colour = match colouring {
+            Colouring::PerWord => words
+                .iter()
+                .find(|(word, _)| *word == m.word)
+                .map_or(0, |(_, colour)| *colour),
+            Colouring::PerMatch => index % PALETTE.len(),
+        };
+        for cell in &m.cells {
+            colours.entry(*cell).or_insert(colour);
+        }
+    }
+    colours
+}
+
+/// The grid with only the matched letters left, one row per line.
+pub fn plain<L: Display>(grid: &Grid<L>, matches: &[Match]) -> String {
+    let colours = paint(matches, Colouring::PerMatch);
+    let mut text = String::new();
+    for (y, row) in grid.iter_rows().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            match colours.get(&(x, y)) {
+                Some(_) => text.push_str(&cell.to_string()),
+                None => text.push(DIMMED),
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+/// The grid for a terminal, with matched letters in bold colours and the
+/// others dimmed.
+pub fn ansi<L: Display>(grid: &Grid<L>, matches: &[Match], colouring: Colouring) -> String {
+    let colours = paint(matches, colouring);
+    let mut text = String::new();
+    for (y, row) in grid.iter_rows().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            match colours.get(&(x, y)) {
+                Some(colour) => {
+                    text.push_str(&format!("\x1b[1;{}m{cell}\x1b[0m", PALETTE[*colour].0))
+                }
+                None => text.push_str(&format!("\x1b[2m{DIMMED}\x1b[0m")),
+            }
+        }
+        text.push('\n');
+    }
+    text
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// The grid as a standalone HTML page, coloured like the terminal view,
+/// with a legend of the words when they are coloured per word.
+pub fn html<L: Display>(
+    grid: &Grid<L>,
+    matches: &[Match],
+    colouring: Colouring,
+    title: &str,
+) -> String {
+    let colours = paint(matches, colouring);
+    let mut page = format!(
+        "\
+<!DOCTYPE html>
+<html>
+<head>
+<meta charset=\"utf-8\">
+<title>{}</title>
+<style>
+body {{ background: #0f0f23; color: #cccccc; font-family: monospace; }}
+pre {{ font-size: 1.2em; }}
+.dim {{ color: #444455; }}
+</style>
+</head>
+<body>
+<pre>
+",
+        escape(title)
+    );
+
+    for (y, row) in grid.iter_rows().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            match colours.get(&(x, y)) {
+                Some(colour) => page.push_str(&format!(
+                    "<b style=\"color: {}\">{}</b>",
+                    PALETTE[*colour].1,
+                    escape(&cell.to_string())
+                )),
+                None => page.push_str(&format!("<span class=\"dim\">{DIMMED}</span>")),
+            }
+        }
+        page.push('\n');
+    }
+    page.push_str("</pre>\n");
+
+    if colouring == Colouring::PerWord {
+        page.push_str("<p>\n");
+        for (word, colour) in word_colours(matches) {
+            page.push_str(&format!(
+                "<b style=\"color: {}\">{}</b>\n",
+                PALETTE[colour].1,
+                escape(word)
+            ));
+        }
+        page.push_str("</p>\n");
+    }
+
+    page.push_str(&format!(
+        "<p>{} matches</p>\n</body>\n</html>\n",
+        matches.len()
+    ));
+    page
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{find_words, Dictionary, Walk, ALL_DIRECTIONS};
+
+    fn sample() -> (Grid<char>, Vec<Match>) {
+        let grid = Grid::build("XMASX\nAB<CM\nXSAMX").unwrap();
+        let matches = find_words(
+            &grid,
+            &Dictionary::new(["XMAS", "XAX"]),
+            &ALL_DIRECTIONS,
+            Walk::Straight,
+        );
+        (grid, matches)
+    }
+
+    #[test]
+    fn render_plain_and_ansi() {
+        let (grid, matches) = sample();
+        assert_eq!(matches.len(), 4);
+        assert_eq!(plain(&grid, &matches), "XMAS.\nA....\nXSAMX\n");
+
+        let by_word = ansi(&grid, &matches, Colouring::PerWord);
+        let first_row = "\x1b[1;31mX\x1b[0m\x1b[1;31mM\x1b[0m\x1b[1;31mA\x1b[0m\x1b[1;31mS\x1b[0m\
+                         \x1b[2m.\x1b[0m\n";
+        assert!(by_word.starts_with(first_row));
+        // the "A" under the first "X" only belongs to "XAX"
+        assert!(by_word.contains("\n\x1b[1;32mA\x1b[0m\x1b[2m.\x1b[0m"));
+        assert!(by_word.ends_with("\x1b[1;31mA\x1b[0m\x1b[1;31mM\x1b[0m\x1b[1;31mX\x1b[0m\n"));
+
+        let by_match = ansi(&grid, &matches, Colouring::PerMatch);
+        assert!(by_match.ends_with("\x1b[1;34mA\x1b[0m\x1b[1;34mM\x1b[0m\x1b[1;34mX\x1b[0m\n"));
+    }
+
+    #[test]
+    fn render_html_page() {
+        let (grid, matches) = sample();
+        let page = html(&grid, &matches, Colouring::PerWord, "Day 4 <XMAS>");
+
+        assert!(page.starts_with("<!DOCTYPE html>\n"));
+        assert!(page.contains("<title>Day 4 &lt;XMAS&gt;</title>"));
+        assert!(page
+            .contains("<pre>\n<b style=\"color: #e06c75\">X</b><b style=\"color: #e06c75\">M</b>"));
+        assert!(page.contains("<b style=\"color: #98c379\">XAX</b>\n"));
+        assert!(page.ends_with("<p>4 matches</p>\n</body>\n</html>\n"));
+    }
+}
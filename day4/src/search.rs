@@ -0,0 +1,423 @@
+use grid::{Grid, Position};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::hash::Hash;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A step from one cell to the next, as `(dx, dy)`.
+pub type Direction = (isize, isize);
+
+/// The eight ways a word can be written: horizontally, vertically and
+/// diagonally, forwards and backwards.
+pub const ALL_DIRECTIONS: [Direction; 8] = grid::NEIGHBOURS_8;
+
+/// The four diagonal ways a word can be written.
+pub const DIAGONALS: [Direction; 4] = [(1, -1), (1, 1), (-1, 1), (-1, -1)];
+
+/// How a word may run through the grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Walk {
+    /// Along a straight line inside the grid.
+    Straight,
+    /// Along a straight line going on across the edges, as on a torus,
+    /// until it would come back to its first cell.
+    Wraparound,
+    /// Along any chain of adjacent cells, Boggle-style, never using a cell
+    /// twice. Every step is one of the directions searched.
+    Path,
+}
+
+/// A word found in the grid. Positions count letters, not bytes.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Match {
+    pub word: String,
+    /// Position of the first letter.
+    pub start: Position,
+    /// The direction of straight words, `None` for bent paths.
+    pub direction: Option<Direction>,
+    /// Position of every letter, from the first one.
+    pub cells: Vec<Position>,
+}
+
+#[derive(Debug, Clone)]
+struct Node<L> {
+    children: HashMap<L, usize>,
+    // index of the word ending here, if any
+    word: Option<usize>,
+}
+
+impl<L> Node<L> {
+    fn new() -> Node<L> {
+        Node {
+            children: HashMap::new(),
+            word: None,
+        }
+    }
+}
+
+/// Words to look for together. They are stored in a trie so that a single
+/// walk from a cell in a direction finds all of them at once.
+///
+/// Letters are Unicode scalar values by default, or grapheme clusters so
+/// that a letter and its combining marks make a single cell.
+#[derive(Debug, Clone)]
+pub struct Dictionary<L = char> {
+    words: Vec<String>,
+    nodes: Vec<Node<L>>,
+}
+
+impl Dictionary {
+    /// Builds a dictionary of scalar values, ignoring empty and repeated
+    /// words.
+    pub fn new<I, S>(words: I) -> Dictionary
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Dictionary::split(words, |word| word.chars().collect())
+    }
+}
+
+impl Dictionary<String> {
+    /// Builds a dictionary of grapheme clusters, ignoring empty and
+    /// repeated words.
+    pub fn graphemes<I, S>(words: I) -> Dictionary<String>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        Dictionary::split(words, |word| {
+            word.graphemes(true).map(str::to_string).collect()
+        })
+    }
+}
+
+impl<L: Eq + Hash> Dictionary<L> {
+    fn split<I, S, F>(words: I, letters: F) -> Dictionary<L>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+        F: Fn(&str) -> Vec<L>,
+    {
+        let mut dictionary = Dictionary {
+            words: Vec::new(),
+            nodes: vec![Node::new()],
+        };
+
+        for word in words {
+            let word = word.as_ref();
+            if word.is_empty() {
+                continue;
+            }
+
+            let mut node = 0;
+            for letter in letters(word) {
+                node = match dictionary.nodes[node].children.get(&letter) {
+                    Some(&child) => child,
+                    None => {
+                        let child = dictionary.nodes.len();
+                        dictionary.nodes.push(Node::new());
+                        dictionary.nodes[node].children.insert(letter, child);
+                        child
+                    }
+                };
+            }
+
+            if dictionary.nodes[node].word.is_none() {
+                dictionary.nodes[node].word = Some(dictionary.words.len());
+                dictionary.words.push(word.to_string());
+            }
+        }
+
+        dictionary
+    }
+
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    fn child(&self, node: usize, letter: &L) -> Option<usize> {
+        self.nodes[node].children.get(letter).copied()
+    }
+}
+
+// The cell after `cell` in `direction` on a torus
+fn wrapped<L>(grid: &Grid<L>, cell: Position, direction: Direction) -> Position {
+    let step = |from: usize, delta: isize, size: usize| {
+        (from as isize + delta).rem_euclid(size as isize) as usize
+    };
+    (
+        step(cell.0, direction.0, grid.columns()),
+        step(cell.1, direction.1, grid.rows()),
+    )
+}
+
+// Straight words from `start` in `direction`
+fn follow_line<L: Eq + Hash>(
+    grid: &Grid<L>,
+    dictionary: &Dictionary<L>,
+    (start, direction): (Position, Direction),
+    walk: Walk,
+    single_letters: bool,
+    matches: &mut Vec<Match>,
+) {
+    let mut node = 0;
+    let mut cells = Vec::new();
+    let mut position = Some(start);
+
+    while let Some(cell) = position {
+        let Some(child) = dictionary.child(node, &grid[cell]) else {
+            break;
+        };
+        node = child;
+        cells.push(cell);
+
+        if let Some(word) = dictionary.nodes[node].word {
+            if cells.len() > 1 || single_letters {
+                matches.push(Match {
+                    word: dictionary.words[word].clone(),
+                    start,
+                    direction: Some(direction),
+                    cells: cells.clone(),
+                });
+            }
+        }
+
+        position = match walk {
+            Walk::Wraparound => Some(wrapped(grid, cell, direction)).filter(|next| *next != start),
+            Walk::Straight | Walk::Path => grid.offset(cell, direction),
+        };
+    }
+}
+
+// Bent words going on from the last of `cells`, the trie being at `node`
+// before its letter
+fn follow_paths<L: Eq + Hash>(
+    grid: &Grid<L>,
+    dictionary: &Dictionary<L>,
+    directions: &[Direction],
+    node: usize,
+    cells: &mut Vec<Position>,
+    matches: &mut Vec<Match>,
+) {
+    let cell = *cells.last().expect("paths start with a cell");
+    let Some(node) = dictionary.child(node, &grid[cell]) else {
+        return;
+    };
+
+    if let Some(word) = dictionary.nodes[node].word {
+        matches.push(Match {
+            word: dictionary.words[word].clone(),
+            start: cells[0],
+            direction: None,
+            cells: cells.clone(),
+        });
+    }
+
+    for &direction in directions {
+        if let Some(next) = grid.offset(cell, direction) {
+            if !cells.contains(&next) {
+                cells.push(next);
+                follow_paths(grid, dictionary, directions, node, cells, matches);
+                cells.pop();
+            }
+        }
+    }
+}
+
+/// Every occurrence of the words of `dictionary` walking through the grid
+/// with steps among `directions`, by starting cell row by row, then by
+/// direction, then by length. A cell sequence is only reported once, so
+/// single letters are found once per cell.
+pub fn find_words<L: Eq + Hash>(
+    grid: &Grid<L>,
+    dictionary: &Dictionary<L>,
+    directions: &[Direction],
+    walk: Walk,
+) -> Vec<Match> {
+    let mut matches = Vec::new();
+    for start in grid.positions() {
+        match walk {
+            Walk::Straight | Walk::Wraparound => {
+                for (index, &direction) in directions.iter().enumerate() {
+                    let line = (start, direction);
+                    follow_line(grid, dictionary, line, walk, index == 0, &mut matches);
+                }
+            }
+            Walk::Path => follow_paths(
+                grid,
+                dictionary,
+                directions,
+                0,
+                &mut vec![start],
+                &mut matches,
+            ),
+        }
+    }
+
+    // on narrow tori, several directions go through the same cells
+    if walk == Walk::Wraparound {
+        let mut seen = HashSet::new();
+        matches.retain(|m| seen.insert(m.cells.clone()));
+    }
+
+    matches
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn find_dictionary_in_one_pass() {
+        let grid = Grid::build(
+            "\
+CATS
+A..O
+R..N",
+        )
+        .unwrap();
+        let dictionary = Dictionary::new(["CAT", "CATS", "CAR", "SON", "", "CAT"]);
+        assert_eq!(dictionary.words(), ["CAT", "CATS", "CAR", "SON"]);
+
+        let matches = find_words(&grid, &dictionary, &ALL_DIRECTIONS, Walk::Straight);
+        let found: Vec<(&str, Position, Option<Direction>)> = matches
+            .iter()
+            .map(|m| (m.word.as_str(), m.start, m.direction))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("CAT", (0, 0), Some((1, 0))),
+                ("CATS", (0, 0), Some((1, 0))),
+                ("CAR", (0, 0), Some((0, 1))),
+                ("SON", (3, 0), Some((0, 1))),
+            ]
+        );
+        assert_eq!(matches[3].cells, [(3, 0), (3, 1), (3, 2)]);
+    }
+
+    #[test]
+    fn single_letters_found_once() {
+        let grid = Grid::build("AB\nBA").unwrap();
+        let matches = find_words(&grid, &Dictionary::new(["A"]), &DIAGONALS, Walk::Straight);
+        let starts: Vec<Position> = matches.iter().map(|m| m.start).collect();
+        assert_eq!(starts, [(0, 0), (1, 1)]);
+    }
+
+    #[test]
+    fn find_words_across_edges() {
+        let grid = Grid::build("ASXM\nQBCD").unwrap();
+        let dictionary = Dictionary::new(["XMAS", "XMASX", "DQ"]);
+
+        let straight = find_words(&grid, &dictionary, &ALL_DIRECTIONS, Walk::Straight);
+        assert!(straight.is_empty());
+
+        let wrapped = find_words(&grid, &dictionary, &ALL_DIRECTIONS, Walk::Wraparound);
+        let found: Vec<(&str, Option<Direction>, &[Position])> = wrapped
+            .iter()
+            .map(|m| (m.word.as_str(), m.direction, m.cells.as_slice()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("XMAS", Some((1, 0)), &[(2, 0), (3, 0), (0, 0), (1, 0)][..]),
+                ("DQ", Some((1, 0)), &[(3, 1), (0, 1)][..]),
+            ]
+        );
+
+        // every direction goes through both cells of a single row
+        let grid = Grid::build("AB").unwrap();
+        let wrapped = find_words(
+            &grid,
+            &Dictionary::new(["AB"]),
+            &ALL_DIRECTIONS,
+            Walk::Wraparound,
+        );
+        assert_eq!(wrapped.len(), 1);
+    }
+
+    #[test]
+    fn find_words_along_paths() {
+        let grid = Grid::build("CAT\nXRS").unwrap();
+        let dictionary = Dictionary::new(["CATS", "CAR", "ARC", "STAR", "TAT"]);
+
+        assert!(find_words(&grid, &dictionary, &ALL_DIRECTIONS, Walk::Straight).is_empty());
+
+        let paths = find_words(&grid, &dictionary, &ALL_DIRECTIONS, Walk::Path);
+        let found: Vec<(&str, Option<Direction>, &[Position])> = paths
+            .iter()
+            .map(|m| (m.word.as_str(), m.direction, m.cells.as_slice()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("CATS", None, &[(0, 0), (1, 0), (2, 0), (2, 1)][..]),
+                ("CAR", None, &[(0, 0), (1, 0), (1, 1)][..]),
+                ("ARC", None, &[(1, 0), (1, 1), (0, 0)][..]),
+                ("STAR", None, &[(2, 1), (2, 0), (1, 0), (1, 1)][..]),
+            ]
+        );
+
+        // only orthogonal steps
+        let paths = find_words(&grid, &dictionary, &grid::NEIGHBOURS_4, Walk::Path);
+        let words: Vec<&str> = paths.iter().map(|m| m.word.as_str()).collect();
+        assert_eq!(words, ["CATS", "CAR", "STAR"]);
+    }
+
+    #[test]
+    fn find_accented_and_cjk_words() {
+        let grid = Grid::build(
+            "\
+écoleà
+日本語ç.!
+été本x?",
+        )
+        .unwrap();
+        let dictionary = Dictionary::new(["école", "日本語", "éét", "à!"]);
+        let matches = find_words(&grid, &dictionary, &ALL_DIRECTIONS, Walk::Straight);
+        let found: Vec<(&str, Position, Option<Direction>)> = matches
+            .iter()
+            .map(|m| (m.word.as_str(), m.start, m.direction))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("école", (0, 0), Some((1, 0))),
+                ("à!", (5, 0), Some((0, 1))),
+                ("日本語", (0, 1), Some((1, 0))),
+            ]
+        );
+        assert_eq!(matches[1].cells, [(5, 0), (5, 1)]);
+
+        // "e" followed by a combining acute accent is two scalar values
+        // but a single grapheme
+        let raw = "e\u{301}te\u{301}s\nabc\u{327}d";
+        assert!(Grid::build(raw).is_err());
+
+        let grid = Grid::parse_cells(raw, |line| line.graphemes(true).map(String::from)).unwrap();
+        assert_eq!(grid.columns(), 4);
+        let dictionary = Dictionary::graphemes(["e\u{301}t", "c\u{327}"]);
+        let matches = find_words(&grid, &dictionary, &ALL_DIRECTIONS, Walk::Straight);
+        let found: Vec<(&str, Position)> =
+            matches.iter().map(|m| (m.word.as_str(), m.start)).collect();
+        assert_eq!(
+            found,
+            [
+                ("e\u{301}t", (0, 0)),
+                ("e\u{301}t", (2, 0)),
+                ("c\u{327}", (2, 1))
+            ]
+        );
+
+        let error = Grid::parse_cells("ab\nc\u{327}", |line| line.graphemes(true)).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 2: expected 2 cells per row, found 1"
+        );
+    }
+}
@@ -0,0 +1,185 @@
+use grid::{Grid, Position};
+use puzzle::PuzzleError;
+
+/// Cell of a stencil file matching any letter.
+pub const WILDCARD: char = '?';
+
+/// The X-MAS of part two: two diagonal "MAS" crossing on their "A".
+pub const X_MAS: &str = "\
+M?S
+?A?
+M?S";
+
+/// Which transformed copies of a stencil are looked for as well.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Orientations {
+    /// The stencil as written.
+    Fixed,
+    /// The stencil turned by 0°, 90°, 180° and 270°.
+    Rotations,
+    /// The rotations and their mirror images.
+    All,
+}
+
+impl Orientations {
+    pub(crate) fn parse(value: &str) -> Result<Orientations, PuzzleError> {
+        match value {
+            "fixed" => Ok(Orientations::Fixed),
+            "rotations" => Ok(Orientations::Rotations),
+            "all" => Ok(Orientations::All),
+            _ => Err(PuzzleError::usage(format!(
+                "Invalid orientations '{value}': must be fixed, rotations or all"
+            ))),
+        }
+    }
+}
+
+/// A small 2-D pattern of letters and wildcards, matched against the word
+/// search at every position where it fits.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Stencil {
+    // `None` for wildcards
+    cells: Grid<Option<char>>,
+}
+
+impl Stencil {
+    /// Reads a stencil with one row per line, where `?` matches any letter.
+    pub fn parse(raw_content: &str) -> Result<Stencil, PuzzleError> {
+        let cells = Grid::parse(raw_content, |c| Some((c != WILDCARD).then_some(c)))?;
+
+        Ok(Stencil { cells })
+    }
+
+    pub fn load(file: &str) -> Result<Stencil, PuzzleError> {
+        let raw_content = puzzle::read_input(file)?;
+        Stencil::parse(&raw_content).map_err(|e| e.in_file(file))
+    }
+
+    // The stencil turned a quarter clockwise
+    fn rotated(&self) -> Stencil {
+        let rows = self.cells.rows();
+        let mut cells = Grid::filled(rows, self.cells.columns(), None);
+        for ((x, y), cell) in self.cells.iter() {
+            cells[(rows - 1 - y, x)] = *cell;
+        }
+
+        Stencil { cells }
+    }
+
+    // The stencil flipped left to right
+    fn mirrored(&self) -> Stencil {
+        let columns = self.cells.columns();
+        let mut cells = Grid::filled(columns, self.cells.rows(), None);
+        for ((x, y), cell) in self.cells.iter() {
+            cells[(columns - 1 - x, y)] = *cell;
+        }
+
+        Stencil { cells }
+    }
+
+    /// The distinct stencils looked for with `orientations`, this one
+    /// first. Symmetric stencils have fewer than four or eight.
+    pub fn variants(&self, orientations: Orientations) -> Vec<Stencil> {
+        let mut bases = vec![self.clone()];
+        if orientations == Orientations::All {
+            bases.push(self.mirrored());
+        }
+
+        let turns = match orientations {
+            Orientations::Fixed => 1,
+            Orientations::Rotations | Orientations::All => 4,
+        };
+
+        let mut variants: Vec<Stencil> = Vec::new();
+        for base in bases {
+            let mut stencil = base;
+            for _ in 0..turns {
+                let next = stencil.rotated();
+                if !variants.contains(&stencil) {
+                    variants.push(stencil);
+                }
+                stencil = next;
+            }
+        }
+
+        variants
+    }
+
+    fn fits_at(&self, grid: &Grid<char>, (left, top): Position) -> bool {
+        self.cells.iter().all(|((x, y), cell)| match cell {
+            Some(letter) => grid[(left + x, top + y)] == *letter,
+            None => true,
+        })
+    }
+
+    /// Top-left positions where this stencil, as written, matches `grid`.
+    pub fn placements<'a>(&'a self, grid: &'a Grid<char>) -> impl Iterator<Item = Position> + 'a {
+        let columns = (grid.columns() + 1).saturating_sub(self.cells.columns());
+        let rows = (grid.rows() + 1).saturating_sub(self.cells.rows());
+        (0..rows)
+            .flat_map(move |y| (0..columns).map(move |x| (x, y)))
+            .filter(move |&position| self.fits_at(grid, position))
+    }
+
+    /// Number of placements of every variant of this stencil in `grid`.
+    pub fn count(&self, grid: &Grid<char>, orientations: Orientations) -> usize {
+        self.variants(orientations)
+            .iter()
+            .map(|variant| variant.placements(grid).count())
+            .sum()
+    }
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stencil_variants() {
+        let corner = Stencil::parse("AB\n?C").unwrap();
+        assert_eq!(corner.variants(Orientations::Fixed).len(), 1);
+        assert_eq!(corner.variants(Orientations::Rotations).len(), 4);
+        assert_eq!(corner.variants(Orientations::All).len(), 8);
+        assert_eq!(
+            corner.variants(Orientations::Rotations)[1],
+            Stencil::parse("?A\nCB").unwrap()
+        );
+
+        let plus = Stencil::parse("?A?\nAAA\n?A?").unwrap();
+        assert_eq!(plus.variants(Orientations::All).len(), 1);
+
+        let x_mas = Stencil::parse(X_MAS).unwrap();
+        assert_eq!(x_mas.variants(Orientations::All).len(), 4);
+
+        assert!(Stencil::parse("AB\nC").is_err());
+    }
+
+    #[test]
+    fn count_placements() {
+        let grid = Grid::build(
+            "\
+.A..
+AAA.
+.AAA
+..A.",
+        )
+        .unwrap();
+
+        let plus = Stencil::parse("?A?\nAAA\n?A?").unwrap();
+        assert_eq!(plus.count(&grid, Orientations::All), 2);
+
+        let block = Stencil::parse("AA\nAA").unwrap();
+        assert_eq!(block.count(&grid, Orientations::Fixed), 1);
+
+        let hook = Stencil::parse("A.\nAA").unwrap();
+        assert_eq!(hook.count(&grid, Orientations::Fixed), 2);
+        assert_eq!(hook.count(&grid, Orientations::Rotations), 6);
+
+        let too_big = Stencil::parse("AAAAA").unwrap();
+        assert_eq!(too_big.count(&grid, Orientations::All), 0);
+    }
+}
@@ -0,0 +1,23 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day5::Day5;
+use puzzle::bench;
+
+fn day5(c: &mut Criterion) {
+    let rules = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/rules-input.txt"
+    ));
+    let updates = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/updates-input.txt"
+    ));
+
+    // the checked-in rules are a CSV, turn them back into the puzzle format
+    let rules: Vec<String> = rules.lines().skip(1).map(|l| l.replace(',', "|")).collect();
+    let raw_input = format!("{}\n\n{updates}", rules.join("\n"));
+
+    bench::phases::<Day5>(c, "day5", &raw_input);
+}
+
+criterion_group!(benches, day5);
+criterion_main!(benches);
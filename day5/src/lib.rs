@@ -1,30 +1,30 @@
 use csv::Reader;
-use itertools::Itertools;
+use puzzle::{PuzzleError, Solution};
 use serde::de::DeserializeOwned;
-use std::error::Error;
-use std::fs;
 use std::io::Read;
 
-#[derive(Debug, serde::Deserialize)]
+#[derive(Debug, Clone, serde::Deserialize)]
 struct Rule {
     first_page: i32,
     second_page: i32,
 }
 
+/// Either a single puzzle input holding both rules and updates, or a rules
+/// file followed by an updates file.
 #[derive(Debug)]
 pub struct Config {
     first_file: String,
-    second_file: String,
+    second_file: Option<String>,
 }
 
 impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
-        if args.len() < 3 {
-            return Err("Not enough arguments");
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
+        if args.len() < 2 {
+            return Err(PuzzleError::usage("Not enough arguments"));
         }
 
         let first_file = args[1].clone();
-        let second_file = args[2].clone();
+        let second_file = args.get(2).cloned();
 
         Ok(Config {
             first_file,
@@ -33,25 +33,23 @@ impl Config {
     }
 }
 
-struct UpdateSet {
+#[derive(Debug, Clone)]
+pub struct UpdateSet {
     rules: Vec<Rule>,
     right_order_updates: Vec<Vec<i32>>,
     invalid_order_updates: Vec<Vec<i32>>,
 }
 
 impl UpdateSet {
-    fn make(raw_content: &str, rules: Vec<Rule>) -> Result<UpdateSet, &'static str> {
-        let lines: Vec<&str> = raw_content.trim().split("\n").collect();
+    fn make(raw_content: &str, rules: Vec<Rule>) -> Result<UpdateSet, PuzzleError> {
+        let updates = parse_updates(raw_content, 1)?;
+        Ok(UpdateSet::from_updates(updates, rules))
+    }
 
+    fn from_updates(updates: Vec<Vec<i32>>, rules: Vec<Rule>) -> UpdateSet {
         let mut right_order_updates = Vec::new();
         let mut invalid_order_updates = Vec::new();
-        for l in lines {
-            let values: Vec<&str> = l.trim().split(",").collect();
-            let values: Vec<i32> = values
-                .iter()
-                .map(|v| v.parse().expect("Cannot convert to i32"))
-                .collect();
-
+        for values in updates {
             if UpdateSet::rules_valid(&values, &rules) {
                 right_order_updates.push(values);
             } else {
@@ -59,14 +57,14 @@ impl UpdateSet {
             }
         }
 
-        Ok(UpdateSet {
+        UpdateSet {
             rules,
             right_order_updates,
             invalid_order_updates,
-        })
+        }
     }
 
-    fn rules_valid(values: &[i32], rules: &Vec<Rule>) -> bool {
+    fn rules_valid(values: &[i32], rules: &[Rule]) -> bool {
         let mut rules_valid = true;
         rules.iter().for_each(|r| {
             let first = values.iter().position(|&x| x == r.first_page);
@@ -81,6 +79,7 @@ impl UpdateSet {
         rules_valid
     }
 
+    #[cfg(test)]
     fn right_order_updates(&self) -> usize {
         self.right_order_updates.len()
     }
@@ -98,7 +97,7 @@ impl UpdateSet {
     }
 
     fn order_wrong_updates_by_rules(&mut self) {
-        for (exterior_index, w) in self.invalid_order_updates.iter_mut().enumerate() {
+        for w in self.invalid_order_updates.iter_mut() {
             let num_items = w.len();
             for index in 0..num_items {
                 let subvector = &mut w[index..];
@@ -106,16 +105,14 @@ impl UpdateSet {
                 let mut redo_rules = true;
                 while redo_rules {
                     redo_rules = false;
-                    for (ix, r) in self.rules.iter().enumerate() {
+                    for r in self.rules.iter() {
                         let first = subvector.iter().position(|&x| x == r.first_page);
                         let second = subvector.iter().position(|&x| x == r.second_page);
 
                         if let (Some(first), Some(second)) = (first, second) {
                             if first >= second {
                                 // swap them
-                                let temp = subvector[first];
-                                subvector[first] = subvector[second];
-                                subvector[second] = temp;
+                                subvector.swap(first, second);
 
                                 redo_rules = true;
                                 break;
@@ -140,7 +137,7 @@ impl UpdateSet {
     }
 }
 
-fn deserialize<T, R>(reader: R) -> Result<Vec<T>, Box<dyn std::error::Error>>
+fn deserialize<T, R>(reader: R) -> Result<Vec<T>, PuzzleError>
 where
     T: std::fmt::Debug + DeserializeOwned,
     R: Read,
@@ -148,24 +145,141 @@ where
     let mut rdr = Reader::from_reader(reader);
     let mut structs: Vec<T> = Vec::new();
     for result in rdr.deserialize() {
-        let record: T = result?;
+        let record: T = result.map_err(PuzzleError::from_csv)?;
         structs.push(record);
     }
 
     Ok(structs)
 }
 
-pub fn run(config: Config) -> Result<(u32, u32), Box<dyn Error>> {
-    let rules = fs::read_to_string(config.first_file)?;
-    let updates = fs::read_to_string(config.second_file)?;
+/// Parses comma-separated updates, `first_line` being the line number of
+/// the first line of `raw_content` in its file.
+fn parse_updates(raw_content: &str, first_line: usize) -> Result<Vec<Vec<i32>>, PuzzleError> {
+    let mut updates = Vec::new();
+    for (index, l) in raw_content.lines().enumerate() {
+        let line_number = first_line + index;
+        if l.trim().is_empty() {
+            continue;
+        }
 
-    let rules: Vec<Rule> = deserialize(rules.as_bytes()).unwrap();
-    let mut updates = UpdateSet::make(&updates, rules).unwrap();
-    let right_order_sum = updates.right_ordered_middle_page_numbers_sum();
+        let values: Vec<i32> = l
+            .split(",")
+            .map(|v| puzzle::parse_number(l, v.trim()))
+            .collect::<Result<_, _>>()
+            .map_err(|e| e.on_line(line_number))?;
+
+        if values.len().is_multiple_of(2) {
+            return Err(PuzzleError::at(
+                line_number,
+                1,
+                "update must have an odd number of pages",
+            ));
+        }
+
+        updates.push(values);
+    }
 
-    updates.order_wrong_updates_by_rules();
-    let invalid_order_sum = updates.wrong_ordered_middle_page_number_sum();
-    Ok((right_order_sum, invalid_order_sum))
+    Ok(updates)
+}
+
+/// Parses `a|b` rule lines, as found at the top of the puzzle input.
+fn parse_pipe_rules(raw_content: &str) -> Result<Vec<Rule>, PuzzleError> {
+    let mut rules = Vec::new();
+    for (index, l) in raw_content.lines().enumerate() {
+        if l.trim().is_empty() {
+            continue;
+        }
+
+        let (first, second) = l
+            .split_once('|')
+            .ok_or_else(|| PuzzleError::at(index + 1, 1, "expected a rule in the form 'a|b'"))?;
+        let parse =
+            |token: &str| puzzle::parse_number(l, token.trim()).map_err(|e| e.on_line(index + 1));
+
+        rules.push(Rule {
+            first_page: parse(first)?,
+            second_page: parse(second)?,
+        });
+    }
+
+    Ok(rules)
+}
+
+fn is_pipe_format(raw_content: &str) -> bool {
+    raw_content
+        .lines()
+        .find(|l| !l.trim().is_empty())
+        .is_some_and(|l| l.contains('|'))
+}
+
+/// Parses rules either as `a|b` lines or as a `first_page,second_page` CSV,
+/// depending on the content.
+fn parse_rules(raw_content: &str) -> Result<Vec<Rule>, PuzzleError> {
+    if is_pipe_format(raw_content) {
+        parse_pipe_rules(raw_content)
+    } else {
+        deserialize(raw_content.as_bytes())
+    }
+}
+
+/// Parses the puzzle input as downloaded: rules, a blank line, then updates.
+fn parse_puzzle_input(raw_content: &str) -> Result<UpdateSet, PuzzleError> {
+    if !is_pipe_format(raw_content) {
+        return Err(PuzzleError::invalid(
+            "expected 'a|b' rules followed by updates, or a separate updates file",
+        ));
+    }
+
+    let lines: Vec<&str> = raw_content.lines().collect();
+    let first_rule = lines.iter().position(|l| !l.trim().is_empty()).unwrap_or(0);
+    let separator = lines[first_rule..]
+        .iter()
+        .position(|l| l.trim().is_empty())
+        .map_or(lines.len(), |index| first_rule + index);
+
+    let rules = parse_pipe_rules(&lines[..separator].join("\n"))?;
+    let updates = match lines.get(separator + 1..) {
+        Some(update_lines) => parse_updates(&update_lines.join("\n"), separator + 2)?,
+        None => Vec::new(),
+    };
+
+    Ok(UpdateSet::from_updates(updates, rules))
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    type Input = UpdateSet;
+    type Answer = u32;
+
+    fn parse(raw_input: &str) -> Result<UpdateSet, PuzzleError> {
+        parse_puzzle_input(raw_input)
+    }
+
+    fn part1(input: &UpdateSet) -> u32 {
+        input.right_ordered_middle_page_numbers_sum()
+    }
+
+    fn part2(input: &UpdateSet) -> u32 {
+        let mut updates = input.clone();
+        updates.order_wrong_updates_by_rules();
+        updates.wrong_ordered_middle_page_number_sum()
+    }
+}
+
+pub fn run(config: Config) -> Result<(u32, u32), PuzzleError> {
+    let first_content = puzzle::read_input(&config.first_file)?;
+
+    let updates = match &config.second_file {
+        Some(second_file) => {
+            let rules = parse_rules(&first_content).map_err(|e| e.in_file(&config.first_file))?;
+            let updates = puzzle::read_input(second_file)?;
+            UpdateSet::make(&updates, rules).map_err(|e| e.in_file(second_file))?
+        }
+        None => parse_puzzle_input(&first_content).map_err(|e| e.in_file(&config.first_file))?,
+    };
+
+    Ok((Day5::part1(&updates), Day5::part2(&updates)))
 }
 
 // Note on printing during tests:
@@ -245,10 +359,20 @@ first_page,second_page
         assert_eq!(updates_set.right_ordered_middle_page_numbers_sum(), 143);
     }
 
+    #[test]
+    fn invalid_page_reports_line_and_column() {
+        let updates = "\
+75,47,61,53,29
+97,61,5x,29,13";
+
+        let error = UpdateSet::make(updates, Vec::new()).unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 7: invalid number '5x'");
+    }
+
     #[test]
     fn violation_validation() {
-        let rules = vec![(47, 53), (97, 13), (97, 61)];
-        let update = vec![75, 47, 61, 53, 29];
+        let rules = [(47, 53), (97, 13), (97, 61)];
+        let update = [75, 47, 61, 53, 29];
 
         let mut rules_valid = true;
         rules.iter().for_each(|&r| {
@@ -352,9 +476,7 @@ first_page,second_page
                     if let (Some(first), Some(second)) = (first, second) {
                         if first >= second {
                             // swap them
-                            let temp = subvector[first];
-                            subvector[first] = subvector[second];
-                            subvector[second] = temp;
+                            subvector.swap(first, second);
 
                             redo_rules = true;
                             break;
@@ -366,4 +488,47 @@ first_page,second_page
 
         assert_eq!(items, vec![97, 75, 47, 29, 13]);
     }
+
+    #[test]
+    fn native_input_sample_validation() {
+        let raw_content = std::fs::read_to_string("content/sample-input.txt").unwrap();
+        let mut updates_set = parse_puzzle_input(&raw_content).unwrap();
+
+        assert_eq!(updates_set.rules.len(), 21);
+        assert_eq!(updates_set.right_order_updates(), 3);
+        assert_eq!(updates_set.right_ordered_middle_page_numbers_sum(), 143);
+
+        updates_set.order_wrong_updates_by_rules();
+        assert_eq!(updates_set.wrong_ordered_middle_page_number_sum(), 123);
+    }
+
+    #[test]
+    fn rules_format_is_detected() {
+        let pipe_rules = parse_rules("47|53\n97|13\n").unwrap();
+        let csv_rules = parse_rules("first_page,second_page\n47,53\n97,13\n").unwrap();
+
+        assert_eq!(pipe_rules.len(), 2);
+        assert_eq!(csv_rules.len(), 2);
+        assert_eq!(pipe_rules[1].first_page, csv_rules[1].first_page);
+        assert_eq!(pipe_rules[1].second_page, csv_rules[1].second_page);
+    }
+
+    #[test]
+    fn native_input_reports_update_line() {
+        let data = "\
+47|53
+97|13
+
+75,47,61
+97,x1,53
+";
+        let error = parse_puzzle_input(data).unwrap_err();
+        assert_eq!(error.to_string(), "line 5, column 4: invalid number 'x1'");
+
+        let error = parse_puzzle_input("47|53\n97-13\n").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 1: expected a rule in the form 'a|b'"
+        );
+    }
 }
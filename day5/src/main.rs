@@ -1,13 +1,33 @@
-use std::{env, error};
+use puzzle::output::Output;
+use std::env;
+use std::time::Instant;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    println!("Hello, day 5!");
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let output = Output::from_args(&mut args);
+    let input = args.get(1).cloned();
 
-    let args: Vec<String> = env::args().collect();
-    let config = day5::Config::build(&args)?;
-    let (middle_page_sum, invalid_middle_page_sum) = day5::run(config)?;
+    if output.is_text() {
+        println!("Hello, day 5!");
+    }
 
-    println!("Middle page sum:         {middle_page_sum}");
-    println!("Invalid middle page sum: {invalid_middle_page_sum}");
-    Ok(())
+    let config = day5::Config::build(&args)
+        .unwrap_or_else(|err| output.fail(5, input.as_deref(), "Problem parsing arguments", &err));
+
+    let start = Instant::now();
+    let (middle_page_sum, invalid_middle_page_sum) = day5::run(config)
+        .unwrap_or_else(|err| output.fail(5, input.as_deref(), "Application error", &err));
+    let elapsed = start.elapsed();
+
+    if output.is_text() {
+        println!("Middle page sum:         {middle_page_sum}");
+        println!("Invalid middle page sum: {invalid_middle_page_sum}");
+    } else {
+        output.answers(
+            5,
+            input.as_deref().unwrap_or_default(),
+            &[&middle_page_sum, &invalid_middle_page_sum],
+            elapsed,
+        );
+    }
 }
@@ -0,0 +1,19 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day6::Day6;
+use puzzle::bench;
+
+fn day6(c: &mut Criterion) {
+    let raw_input = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/puzzle-input.txt"
+    ));
+    bench::phases::<Day6>(c, "day6", &raw_input);
+}
+
+criterion_group! {
+    name = benches;
+    // part 2 replays the whole guard walk once per candidate obstacle
+    config = Criterion::default().sample_size(10);
+    targets = day6
+}
+criterion_main!(benches);
@@ -1,6 +1,6 @@
+use grid::Grid;
+use puzzle::{PuzzleError, Solution};
 use std::collections::HashSet;
-use std::io::{self, Read};
-use std::{error::Error, fs, fs::File, process};
 
 #[derive(Debug)]
 pub struct Config {
@@ -9,16 +9,18 @@ pub struct Config {
 }
 
 impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
         if args.len() < 3 {
-            return Err("Not enough arguments");
+            return Err(PuzzleError::usage("Not enough arguments"));
         }
 
         let puzzle_input = args[1].clone();
         let guard = match args[2].chars().next() {
             Some(c) if args[2].len() == 1 => c,
             _ => {
-                return Err("Invalid second arguments: must be a single char.");
+                return Err(PuzzleError::usage(
+                    "Invalid second arguments: must be a single char.",
+                ));
             }
         };
 
@@ -38,21 +40,22 @@ enum GuardDirection {
 }
 
 impl GuardDirection {
-    fn build(guard_char: char) -> Result<GuardDirection, &'static str> {
+    fn build(guard_char: char) -> Result<GuardDirection, PuzzleError> {
         match guard_char {
             '^' => Ok(GuardDirection::Up),
             '>' => Ok(GuardDirection::Right),
             'v' => Ok(GuardDirection::Down),
             '<' => Ok(GuardDirection::Left),
-            _ => Err("Invalid guard char"),
+            _ => Err(PuzzleError::usage(format!(
+                "Invalid guard char '{guard_char}'"
+            ))),
         }
     }
 }
 
-struct LevelMap {
-    cells: Vec<Vec<char>>,
-    x_max: usize,
-    y_max: usize,
+#[derive(Debug, Clone)]
+pub struct LevelMap {
+    cells: Grid<char>,
     guard_start_position: (usize, usize),
     guard_start_direction: GuardDirection,
     obstacle: Option<(usize, usize)>,
@@ -64,53 +67,23 @@ struct LevelMap {
 }
 
 impl LevelMap {
-    fn build(raw_data: &str, guard: char) -> Result<LevelMap, &'static str> {
-        let lines: Vec<&str> = raw_data.trim().split("\n").collect();
-        let y_max = lines.len();
-        if y_max == 0 {
-            return Err("No lines to parse");
-        }
-
-        let x_max = lines[0].len();
-        if x_max == 0 {
-            return Err("Empty line");
-        }
-
-        let cells: Vec<Vec<char>> = lines.iter().map(|s| s.chars().collect()).collect();
-
-        // ensure all rows are correct
-        let mut cells_ok = true;
-        cells.iter().for_each(|l| {
-            if l.len() != x_max {
-                cells_ok = false;
-            }
-        });
-
-        if !cells_ok {
-            return Err("Variable length lines");
-        }
+    fn build(raw_data: &str, guard: char) -> Result<LevelMap, PuzzleError> {
+        let cells = Grid::build(raw_data)?;
 
         // search for the start
-        let mut position: (usize, usize) = (0, 0);
-        cells.iter().enumerate().for_each(|(y, value)| {
-            value.iter().enumerate().for_each(|(x, c)| {
-                if *c == guard {
-                    position = (x, y);
-                }
-            });
-        });
+        let position = cells
+            .find(&guard)
+            .ok_or_else(|| PuzzleError::invalid(format!("guard '{guard}' not found")))?;
 
         let guard_start_position = position;
 
-        let direction = GuardDirection::build(guard).unwrap();
+        let direction = GuardDirection::build(guard)?;
         let guard_start_direction = direction;
 
         let obstacle = None;
 
         Ok(LevelMap {
             cells,
-            x_max,
-            y_max,
             guard_start_position,
             guard_start_direction,
             position,
@@ -145,7 +118,7 @@ impl LevelMap {
 
     fn move_to_next_cell(&mut self) -> bool {
         // up to 4 possible cells
-        for ix in 0..4 {
+        for _ix in 0..4 {
             let (delta, next_direction) = match self.direction {
                 GuardDirection::Up => ((0, -1), GuardDirection::Right),
                 GuardDirection::Right => ((1, 0), GuardDirection::Down),
@@ -153,13 +126,7 @@ impl LevelMap {
                 GuardDirection::Left => ((-1, 0), GuardDirection::Up),
             };
 
-            let next_x: i32 = self.position.0 as i32 + delta.0;
-            let next_y: i32 = self.position.1 as i32 + delta.1;
-            let next_pos = (next_x, next_y);
-
-            if self.is_cell_inside_map(&next_pos) {
-                let next_pos = (next_x as usize, next_y as usize);
-
+            if let Some(next_pos) = self.cells.offset(self.position, delta) {
                 if self.is_cell_free(&next_pos) {
                     self.position = next_pos;
                     self.unique_positions_to_exit.insert(self.position);
@@ -183,27 +150,16 @@ impl LevelMap {
 
         // fallback: in this case we tried all possible attempts
         panic!("Attempted all possible directions!");
-        false
     }
 
     fn is_cell_free(&self, position: &(usize, usize)) -> bool {
-        assert!(position.0 < self.x_max);
-        assert!(position.1 < self.y_max);
-
         if let Some(obstacle_position) = self.obstacle {
             if obstacle_position == *position {
                 return false;
             }
         }
 
-        self.cells[position.1][position.0] != '#'
-    }
-
-    fn is_cell_inside_map(&self, position: &(i32, i32)) -> bool {
-        position.0 >= 0
-            && position.0 < self.x_max as i32
-            && position.1 >= 0
-            && position.1 < self.y_max as i32
+        self.cells[*position] != '#'
     }
 
     fn total_unique_positions(&self) -> u32 {
@@ -211,39 +167,62 @@ impl LevelMap {
     }
 }
 
-fn compute_total_unique_positions(raw_data: &str, guard: char) -> u32 {
-    let mut map = LevelMap::build(raw_data, guard).unwrap();
-    map.move_to_exit();
-    map.total_unique_positions()
-}
+/// Solves the puzzle for the usual guard, starting upwards as `^`.
+pub struct Day6;
 
-fn compute_total_obstacles_positions(raw_data: &str, guard: char) -> u32 {
-    // first get the "critical path"
-    let mut map = LevelMap::build(raw_data, guard).unwrap();
-    map.move_to_exit();
+impl Solution for Day6 {
+    type Input = LevelMap;
+    type Answer = u32;
 
-    let path = map.unique_positions_to_exit.clone();
-    let mut num_obstacles: u32 = 0;
-    for pos in path {
-        map.reset();
-        map.set_obstacle(pos);
+    fn parse(raw_input: &str) -> Result<LevelMap, PuzzleError> {
+        LevelMap::build(raw_input, '^')
+    }
+
+    fn part1(input: &LevelMap) -> u32 {
+        let mut map = input.clone();
         map.move_to_exit();
+        map.total_unique_positions()
+    }
 
-        if map.loop_found {
-            num_obstacles += 1;
+    fn part2(input: &LevelMap) -> u32 {
+        // first get the "critical path"
+        let mut map = input.clone();
+        map.move_to_exit();
+
+        let path = map.unique_positions_to_exit.clone();
+        let mut num_obstacles: u32 = 0;
+        for pos in path {
+            map.reset();
+            map.set_obstacle(pos);
+            map.move_to_exit();
+
+            if map.loop_found {
+                num_obstacles += 1;
+            }
         }
+
+        num_obstacles
     }
+}
 
-    num_obstacles
+#[cfg(test)]
+fn compute_total_unique_positions(raw_data: &str, guard: char) -> Result<u32, PuzzleError> {
+    let map = LevelMap::build(raw_data, guard)?;
+    Ok(Day6::part1(&map))
 }
 
-pub fn run(config: Config) -> Result<(u32, u32), Box<dyn Error>> {
-    let content = fs::read_to_string(config.puzzle_input)?;
-    let guard = config.guard;
-    let total = compute_total_unique_positions(&content, guard);
-    let total_obstacles = compute_total_obstacles_positions(&content, guard);
+#[cfg(test)]
+fn compute_total_obstacles_positions(raw_data: &str, guard: char) -> Result<u32, PuzzleError> {
+    let map = LevelMap::build(raw_data, guard)?;
+    Ok(Day6::part2(&map))
+}
+
+pub fn run(config: Config) -> Result<(u32, u32), PuzzleError> {
+    let content = puzzle::read_input(&config.puzzle_input)?;
+    let map =
+        LevelMap::build(&content, config.guard).map_err(|e| e.in_file(&config.puzzle_input))?;
 
-    Ok((total, total_obstacles))
+    Ok((Day6::part1(&map), Day6::part2(&map)))
 }
 
 // Note on printing during tests:
@@ -252,8 +231,6 @@ pub fn run(config: Config) -> Result<(u32, u32), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use io::BufReader;
-
     use super::*;
 
     #[test]
@@ -271,8 +248,8 @@ mod tests {
 ......#...";
 
         let level_map = LevelMap::build(data, '^').unwrap();
-        assert_eq!(level_map.x_max, 10);
-        assert_eq!(level_map.y_max, 10);
+        assert_eq!(level_map.cells.columns(), 10);
+        assert_eq!(level_map.cells.rows(), 10);
         assert_eq!(level_map.position, (4, 6));
         assert_eq!(level_map.direction, GuardDirection::Up);
         assert_eq!(level_map.unique_positions_to_exit.len(), 0);
@@ -292,7 +269,7 @@ mod tests {
 #.........
 ......#...";
         let guard = '^';
-        assert_eq!(compute_total_unique_positions(data, guard), 41);
+        assert_eq!(compute_total_unique_positions(data, guard).unwrap(), 41);
     }
 
     #[test]
@@ -309,6 +286,6 @@ mod tests {
 #.........
 ......#...";
         let guard = '^';
-        assert_eq!(compute_total_obstacles_positions(data, guard), 6);
+        assert_eq!(compute_total_obstacles_positions(data, guard).unwrap(), 6);
     }
 }
@@ -1,13 +1,33 @@
-use std::{env, error};
+use puzzle::output::Output;
+use std::env;
+use std::time::Instant;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    println!("Hello, day 6!");
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let output = Output::from_args(&mut args);
+    let input = args.get(1).cloned();
 
-    let args: Vec<String> = env::args().collect();
-    let config = day6::Config::build(&args)?;
-    let (total, total_obstacles) = day6::run(config)?;
+    if output.is_text() {
+        println!("Hello, day 6!");
+    }
 
-    println!("Num total positions:            {total}");
-    println!("Num total obstacles positions:  {total_obstacles}");
-    Ok(())
+    let config = day6::Config::build(&args)
+        .unwrap_or_else(|err| output.fail(6, input.as_deref(), "Problem parsing arguments", &err));
+
+    let start = Instant::now();
+    let (total, total_obstacles) = day6::run(config)
+        .unwrap_or_else(|err| output.fail(6, input.as_deref(), "Application error", &err));
+    let elapsed = start.elapsed();
+
+    if output.is_text() {
+        println!("Num total positions:            {total}");
+        println!("Num total obstacles positions:  {total_obstacles}");
+    } else {
+        output.answers(
+            6,
+            input.as_deref().unwrap_or_default(),
+            &[&total, &total_obstacles],
+            elapsed,
+        );
+    }
 }
@@ -0,0 +1,14 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use day8::Day8;
+use puzzle::bench;
+
+fn day8(c: &mut Criterion) {
+    let raw_input = bench::read_input(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/content/puzzle-input.txt"
+    ));
+    bench::phases::<Day8>(c, "day8", &raw_input);
+}
+
+criterion_group!(benches, day8);
+criterion_main!(benches);
@@ -1,9 +1,7 @@
+use grid::Grid;
+use puzzle::{PuzzleError, Solution};
 use std::collections::HashMap;
 use std::collections::HashSet;
-use std::fs;
-use std::hash::Hash;
-use std::io::{self, Read};
-use std::{error::Error, fs::File, process};
 
 #[derive(Debug)]
 pub struct Config {
@@ -11,9 +9,9 @@ pub struct Config {
 }
 
 impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
         if args.len() < 2 {
-            return Err("Not enough arguments");
+            return Err(PuzzleError::usage("Not enough arguments"));
         }
 
         let puzzle_input = args[1].clone();
@@ -22,15 +20,34 @@ impl Config {
     }
 }
 
+/// Where antinodes appear along the line through two antennas of the
+/// same frequency.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum AntinodeModel {
+    /// One on each side, as far from the nearest antenna as the antennas
+    /// are from each other.
+    Pairs,
+    /// At every multiple of the distance between the antennas, antennas
+    /// included.
+    Harmonics,
+}
+
 #[derive(Debug)]
 pub struct AntennasMap {
-    antinodes_positions: HashMap<char, Vec<(usize, usize)>>,
+    map: Grid<char>,
+    antennas_positions: HashMap<char, Vec<(usize, usize)>>,
 }
 
 impl AntennasMap {
-    pub fn count_unique_antinodes(&self) -> usize {
+    fn count_unique_antinodes(&self, model: AntinodeModel) -> usize {
+        let antinodes_positions = AntennasMapFactory::compute_antinode_positions(
+            &self.antennas_positions,
+            &self.map,
+            model,
+        );
+
         let mut unique_antinodes = HashSet::new();
-        self.antinodes_positions.iter().for_each(|element| {
+        antinodes_positions.iter().for_each(|element| {
             element.1.iter().for_each(|pos| {
                 unique_antinodes.insert(*pos);
             })
@@ -43,43 +60,25 @@ impl AntennasMap {
 struct AntennasMapFactory {}
 
 impl AntennasMapFactory {
-    pub fn make(raw_content: &str) -> Result<AntennasMap, &'static str> {
-        let lines: Vec<String> = raw_content
-            .trim()
-            .split("\n")
-            .map(|s| s.to_string())
-            .collect();
-
-        if lines.len() == 0 {
-            return Err("No lines read from raw content.");
-        }
-
-        if lines[0].len() == 0 {
-            return Err("Read empty line.");
-        }
-
-        let y_max = lines.len();
-        let x_max = lines[0].len();
+    pub fn make(raw_content: &str) -> Result<AntennasMap, PuzzleError> {
+        let map = Grid::build(raw_content)?;
 
-        let antennas_positions = Self::compute_antenna_positions(&lines);
-        let antinodes_positions =
-            Self::compute_antinode_positions(antennas_positions, x_max, y_max);
+        let antennas_positions = Self::compute_antenna_positions(&map);
 
         Ok(AntennasMap {
-            antinodes_positions,
+            map,
+            antennas_positions,
         })
     }
 
-    fn compute_antenna_positions(lines: &Vec<String>) -> HashMap<char, Vec<(usize, usize)>> {
+    fn compute_antenna_positions(map: &Grid<char>) -> HashMap<char, Vec<(usize, usize)>> {
         let mut positions = HashMap::new();
-        for (y, l) in lines.iter().enumerate() {
-            for (x, c) in l.char_indices() {
-                if c.is_ascii_alphanumeric() {
-                    positions
-                        .entry(c)
-                        .and_modify(|list: &mut Vec<(usize, usize)>| list.push((x, y)))
-                        .or_insert(vec![(x, y)]);
-                }
+        for ((x, y), c) in map.iter() {
+            if c.is_ascii_alphanumeric() {
+                positions
+                    .entry(*c)
+                    .and_modify(|list: &mut Vec<(usize, usize)>| list.push((x, y)))
+                    .or_insert(vec![(x, y)]);
             }
         }
 
@@ -87,9 +86,9 @@ impl AntennasMapFactory {
     }
 
     fn compute_antinode_positions(
-        antennas_positions: HashMap<char, Vec<(usize, usize)>>,
-        x_max: usize,
-        y_max: usize,
+        antennas_positions: &HashMap<char, Vec<(usize, usize)>>,
+        map: &Grid<char>,
+        model: AntinodeModel,
     ) -> HashMap<char, Vec<(usize, usize)>> {
         let mut antinodes_map = HashMap::new();
         for (frequency, positions) in antennas_positions {
@@ -104,15 +103,15 @@ impl AntennasMapFactory {
                         *position,
                         *other_position,
                         distance,
-                        x_max,
-                        y_max,
+                        map,
+                        model,
                     );
 
                     antinodes_for_frequency.append(&mut antinodes);
                 }
             }
 
-            antinodes_map.insert(frequency, antinodes_for_frequency);
+            antinodes_map.insert(*frequency, antinodes_for_frequency);
         }
 
         antinodes_map
@@ -121,80 +120,71 @@ impl AntennasMapFactory {
     fn compute_distance(
         first_antenna: (usize, usize),
         second_antenna: (usize, usize),
-    ) -> (i32, i32) {
+    ) -> (isize, isize) {
         (
-            i32::try_from(second_antenna.0).unwrap() - i32::try_from(first_antenna.0).unwrap(),
-            i32::try_from(second_antenna.1).unwrap() - i32::try_from(first_antenna.1).unwrap(),
+            isize::try_from(second_antenna.0).unwrap() - isize::try_from(first_antenna.0).unwrap(),
+            isize::try_from(second_antenna.1).unwrap() - isize::try_from(first_antenna.1).unwrap(),
         )
     }
 
-    fn is_valid_antinode(position: (i32, i32), x_max: usize, y_max: usize) -> bool {
-        position.0 >= 0
-            && usize::try_from(position.0).unwrap() < x_max
-            && position.1 >= 0
-            && usize::try_from(position.1).unwrap() < y_max
-    }
-
     fn compute_antinodes_for_antenna_pair(
         first_antenna: (usize, usize),
         second_antenna: (usize, usize),
-        distance: (i32, i32),
-        x_max: usize,
-        y_max: usize,
+        distance: (isize, isize),
+        map: &Grid<char>,
+        model: AntinodeModel,
     ) -> Vec<(usize, usize)> {
         let mut antinodes = Vec::new();
 
-        let mut antinode = (
-            i32::try_from(first_antenna.0).unwrap(),
-            i32::try_from(first_antenna.1).unwrap(),
-        );
+        if model == AntinodeModel::Pairs {
+            antinodes.extend(map.offset(first_antenna, (-distance.0, -distance.1)));
+            antinodes.extend(map.offset(second_antenna, distance));
+            return antinodes;
+        }
 
+        let mut antinode = first_antenna;
         antinodes.push(antinode);
 
-        loop {
-            antinode = (antinode.0 - distance.0, antinode.1 - distance.1);
-            if Self::is_valid_antinode(antinode, x_max, y_max) {
-                antinodes.push(antinode);
-            } else {
-                break;
-            }
+        while let Some(next) = map.offset(antinode, (-distance.0, -distance.1)) {
+            antinode = next;
+            antinodes.push(antinode);
         }
 
-        let mut antinode = (
-            i32::try_from(second_antenna.0).unwrap(),
-            i32::try_from(second_antenna.1).unwrap(),
-        );
-
+        let mut antinode = second_antenna;
         antinodes.push(antinode);
 
-        loop {
-            antinode = (antinode.0 + distance.0, antinode.1 + distance.1);
-            if Self::is_valid_antinode(antinode, x_max, y_max) {
-                antinodes.push(antinode);
-            } else {
-                break;
-            }
+        while let Some(next) = map.offset(antinode, distance) {
+            antinode = next;
+            antinodes.push(antinode);
         }
 
         antinodes
-            .iter()
-            .map(|position| {
-                (
-                    usize::try_from(position.0).unwrap(),
-                    usize::try_from(position.1).unwrap(),
-                )
-            })
-            .collect()
     }
 }
 
-impl AntennasMap {}
+pub struct Day8;
+
+impl Solution for Day8 {
+    type Input = AntennasMap;
+    type Answer = usize;
+
+    fn parse(raw_input: &str) -> Result<AntennasMap, PuzzleError> {
+        AntennasMapFactory::make(raw_input)
+    }
+
+    fn part1(input: &AntennasMap) -> usize {
+        input.count_unique_antinodes(AntinodeModel::Pairs)
+    }
 
-pub fn run(config: Config) -> Result<(usize), Box<dyn Error>> {
-    let raw_content = fs::read_to_string(config.puzzle_input)?;
-    let antennas_map = AntennasMapFactory::make(&raw_content).unwrap();
-    let unique_antinodes = antennas_map.count_unique_antinodes();
-    Ok((unique_antinodes))
+    fn part2(input: &AntennasMap) -> usize {
+        input.count_unique_antinodes(AntinodeModel::Harmonics)
+    }
+}
+
+pub fn run(config: Config) -> Result<(usize, usize), PuzzleError> {
+    let raw_content = puzzle::read_input(&config.puzzle_input)?;
+
+    Day8::solve(&raw_content).map_err(|e| e.in_file(&config.puzzle_input))
 }
 
 // Note on printing during tests:
@@ -203,8 +193,6 @@ pub fn run(config: Config) -> Result<(usize), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use io::BufReader;
-
     use super::*;
 
     #[test]
@@ -224,6 +212,13 @@ mod tests {
 ............";
 
         let antennas_map = AntennasMapFactory::make(data).unwrap();
-        assert_eq!(antennas_map.count_unique_antinodes(), 34);
+        assert_eq!(
+            antennas_map.count_unique_antinodes(AntinodeModel::Pairs),
+            14
+        );
+        assert_eq!(
+            antennas_map.count_unique_antinodes(AntinodeModel::Harmonics),
+            34
+        );
     }
 }
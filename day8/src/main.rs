@@ -1,12 +1,33 @@
-use std::{env, error};
+use puzzle::output::Output;
+use std::env;
+use std::time::Instant;
 
-fn main() -> Result<(), Box<dyn error::Error>> {
-    println!("Hello, day 8!");
+fn main() {
+    let mut args: Vec<String> = env::args().collect();
+    let output = Output::from_args(&mut args);
+    let input = args.get(1).cloned();
 
-    let args: Vec<String> = env::args().collect();
-    let config = day8::Config::build(&args)?;
-    let (unique_antinodes) = day8::run(config)?;
+    if output.is_text() {
+        println!("Hello, day 8!");
+    }
 
-    println!("Num total unique_antinodes:         {unique_antinodes}");
-    Ok(())
+    let config = day8::Config::build(&args)
+        .unwrap_or_else(|err| output.fail(8, input.as_deref(), "Problem parsing arguments", &err));
+
+    let start = Instant::now();
+    let (unique_antinodes, unique_harmonic_antinodes) = day8::run(config)
+        .unwrap_or_else(|err| output.fail(8, input.as_deref(), "Application error", &err));
+    let elapsed = start.elapsed();
+
+    if output.is_text() {
+        println!("Num unique antinodes:               {unique_antinodes}");
+        println!("Num total unique_antinodes:         {unique_harmonic_antinodes}");
+    } else {
+        output.answers(
+            8,
+            input.as_deref().unwrap_or_default(),
+            &[&unique_antinodes, &unique_harmonic_antinodes],
+            elapsed,
+        );
+    }
 }
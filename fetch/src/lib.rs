@@ -0,0 +1,348 @@
+use std::path::{Path, PathBuf};
+use std::{env, error, fmt, fs, io};
+
+const DEFAULT_BASE_URL: &str = "https://adventofcode.com/2024";
+const USER_AGENT: &str = "github.com/allep/aoc2024 input fetcher";
+
+/// Error raised while looking up or downloading a puzzle input.
+#[derive(Debug)]
+pub enum FetchError {
+    /// The input is not cached and no session token was configured.
+    MissingSession { day: u32 },
+    /// The cache or the session file could not be accessed.
+    Io { path: PathBuf, source: io::Error },
+    /// The server could not be reached or refused the request.
+    Http { url: String, description: String },
+}
+
+impl fmt::Display for FetchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FetchError::MissingSession { day } => write!(
+                f,
+                "input for day {day} is not cached and no session token is set \
+                 (use AOC_SESSION or AOC_SESSION_FILE)"
+            ),
+            FetchError::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            FetchError::Http { url, description } => write!(f, "{url}: {description}"),
+        }
+    }
+}
+
+impl error::Error for FetchError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            FetchError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// Looks puzzle inputs up in a local cache, downloading and caching the
+/// missing ones.
+#[derive(Debug)]
+pub struct InputProvider {
+    cache_dir: PathBuf,
+    base_url: String,
+    session: Option<String>,
+}
+
+impl InputProvider {
+    pub fn new(
+        cache_dir: impl Into<PathBuf>,
+        base_url: impl Into<String>,
+        session: Option<String>,
+    ) -> InputProvider {
+        InputProvider {
+            cache_dir: cache_dir.into(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            session,
+        }
+    }
+
+    /// Builds a provider from the environment:
+    /// - `AOC_CACHE_DIR`, defaulting to `$XDG_CACHE_HOME/aoc2024` or
+    ///   `$HOME/.cache/aoc2024`;
+    /// - `AOC_BASE_URL`, defaulting to the Advent of Code 2024 site;
+    /// - `AOC_SESSION`, or else the content of `AOC_SESSION_FILE`, defaulting
+    ///   to `$HOME/.config/aoc/session`.
+    pub fn from_env() -> Result<InputProvider, FetchError> {
+        let home = env::var_os("HOME").map(PathBuf::from);
+
+        let cache_dir = match env::var_os("AOC_CACHE_DIR") {
+            Some(dir) => PathBuf::from(dir),
+            None => env::var_os("XDG_CACHE_HOME")
+                .map(PathBuf::from)
+                .or_else(|| home.as_ref().map(|h| h.join(".cache")))
+                .unwrap_or_else(env::temp_dir)
+                .join("aoc2024"),
+        };
+
+        let base_url = env::var("AOC_BASE_URL").unwrap_or_else(|_| DEFAULT_BASE_URL.to_string());
+
+        let session = match env::var("AOC_SESSION") {
+            Ok(token) => non_empty(&token),
+            Err(_) => {
+                let file = env::var_os("AOC_SESSION_FILE")
+                    .map(PathBuf::from)
+                    .or_else(|| home.map(|h| h.join(".config/aoc/session")));
+                match file {
+                    Some(file) => read_session_file(&file)?,
+                    None => None,
+                }
+            }
+        };
+
+        Ok(InputProvider::new(cache_dir, base_url, session))
+    }
+
+    /// Where the input of `day` is, or would be, cached.
+    pub fn cached_path(&self, day: u32) -> PathBuf {
+        self.cache_dir.join(format!("day{day}.txt"))
+    }
+
+    pub fn is_cached(&self, day: u32) -> bool {
+        self.cached_path(day).is_file()
+    }
+
+    pub fn has_session(&self) -> bool {
+        self.session.is_some()
+    }
+
+    /// Path to the input of `day`, downloading it first if it is not cached.
+    pub fn input(&self, day: u32) -> Result<PathBuf, FetchError> {
+        let path = self.cached_path(day);
+        if path.is_file() {
+            return Ok(path);
+        }
+
+        let session = self
+            .session
+            .as_ref()
+            .ok_or(FetchError::MissingSession { day })?;
+        let content = self.download(day, session)?;
+        self.store(&path, &content)?;
+
+        Ok(path)
+    }
+
+    fn download(&self, day: u32, session: &str) -> Result<String, FetchError> {
+        let url = format!("{}/day/{day}/input", self.base_url);
+        let http_error = |description: String| FetchError::Http {
+            url: url.clone(),
+            description,
+        };
+
+        let agent = ureq::AgentBuilder::new().user_agent(USER_AGENT).build();
+        let response = agent
+            .get(&url)
+            .set("Cookie", &format!("session={session}"))
+            .call()
+            .map_err(|e| match e {
+                ureq::Error::Status(code, response) => {
+                    http_error(format!("server replied {code} {}", response.status_text()))
+                }
+                ureq::Error::Transport(transport) => http_error(transport.to_string()),
+            })?;
+
+        response
+            .into_string()
+            .map_err(|e| http_error(format!("unreadable response: {e}")))
+    }
+
+    // Inputs are personal, so both the cache directory and the files are
+    // only readable by their owner. The file is written aside and renamed
+    // so that an interrupted download never leaves a truncated input.
+    fn store(&self, path: &Path, content: &str) -> Result<(), FetchError> {
+        let io_error = |path: &Path| {
+            let path = path.to_path_buf();
+            move |source| FetchError::Io { path, source }
+        };
+
+        create_private_dir(&self.cache_dir).map_err(io_error(&self.cache_dir))?;
+
+        let partial = path.with_extension("txt.part");
+        write_private_file(&partial, content).map_err(io_error(&partial))?;
+        fs::rename(&partial, path).map_err(io_error(path))
+    }
+}
+
+fn non_empty(token: &str) -> Option<String> {
+    let token = token.trim();
+    if token.is_empty() {
+        None
+    } else {
+        Some(token.to_string())
+    }
+}
+
+fn read_session_file(file: &Path) -> Result<Option<String>, FetchError> {
+    match fs::read_to_string(file) {
+        Ok(token) => Ok(non_empty(&token)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(source) => Err(FetchError::Io {
+            path: file.to_path_buf(),
+            source,
+        }),
+    }
+}
+
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+#[cfg(unix)]
+fn write_private_file(file: &Path, content: &str) -> io::Result<()> {
+    use std::io::Write;
+    use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+    let mut f = fs::OpenOptions::new()
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .mode(0o600)
+        .open(file)?;
+    // mode() only applies to newly created files
+    f.set_permissions(fs::Permissions::from_mode(0o600))?;
+    f.write_all(content.as_bytes())
+}
+
+#[cfg(not(unix))]
+fn write_private_file(file: &Path, content: &str) -> io::Result<()> {
+    fs::write(file, content)
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    // Serves a single request with `status` and `body`, handing back the
+    // request head that was received.
+    fn mock_server(
+        status: &'static str,
+        body: &'static str,
+    ) -> (String, thread::JoinHandle<String>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base_url = format!("http://{}/2024", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line.trim().is_empty() {
+                    break;
+                }
+                request.push_str(&line);
+            }
+
+            write!(
+                stream,
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                body.len()
+            )
+            .unwrap();
+            request
+        });
+
+        (base_url, handle)
+    }
+
+    #[test]
+    fn download_and_cache_input() {
+        let cache = tempfile::tempdir().unwrap();
+        let cache_dir = cache.path().join("inputs");
+        let (base_url, server) = mock_server("200 OK", "3   4\n4   3\n");
+
+        let provider = InputProvider::new(&cache_dir, base_url, Some("abc123".to_string()));
+        assert!(!provider.is_cached(1));
+
+        let path = provider.input(1).unwrap();
+        assert_eq!(path, cache_dir.join("day1.txt"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "3   4\n4   3\n");
+
+        let request = server.join().unwrap();
+        assert!(request.starts_with("GET /2024/day/1/input HTTP/1.1"));
+        assert!(request.contains("session=abc123"));
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = |p: &Path| fs::metadata(p).unwrap().permissions().mode() & 0o777;
+            assert_eq!(mode(&path), 0o600);
+            assert_eq!(mode(&cache_dir), 0o700);
+        }
+    }
+
+    #[test]
+    fn cached_input_is_not_downloaded() {
+        let cache = tempfile::tempdir().unwrap();
+        fs::write(cache.path().join("day5.txt"), "47|53\n").unwrap();
+
+        // nothing listens there, so any download attempt would fail
+        let provider = InputProvider::new(cache.path(), "http://127.0.0.1:1", None);
+        let path = provider.input(5).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap(), "47|53\n");
+    }
+
+    #[test]
+    fn missing_session_is_reported() {
+        let cache = tempfile::tempdir().unwrap();
+        let provider = InputProvider::new(cache.path(), DEFAULT_BASE_URL, None);
+
+        let error = provider.input(2).unwrap_err();
+        assert!(matches!(error, FetchError::MissingSession { day: 2 }));
+    }
+
+    #[test]
+    fn http_errors_are_not_cached() {
+        let cache = tempfile::tempdir().unwrap();
+        let (base_url, server) = mock_server(
+            "404 Not Found",
+            "Please don't repeatedly request this endpoint",
+        );
+
+        let provider = InputProvider::new(cache.path(), base_url, Some("abc123".to_string()));
+        let error = provider.input(25).unwrap_err();
+        server.join().unwrap();
+
+        assert!(error.to_string().ends_with("server replied 404 Not Found"));
+        assert!(!provider.is_cached(25));
+    }
+
+    #[test]
+    fn session_file_is_trimmed() {
+        let dir = tempfile::tempdir().unwrap();
+        let file = dir.path().join("session");
+        fs::write(&file, "abc123\n").unwrap();
+
+        assert_eq!(
+            read_session_file(&file).unwrap(),
+            Some("abc123".to_string())
+        );
+        assert_eq!(
+            read_session_file(&dir.path().join("missing")).unwrap(),
+            None
+        );
+    }
+}
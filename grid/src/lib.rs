@@ -0,0 +1,399 @@
+use puzzle::PuzzleError;
+use std::fmt;
+use std::ops::{Index, IndexMut};
+
+/// A cell position expressed as `(x, y)`, that is `(column, row)`.
+pub type Position = (usize, usize);
+
+/// Offsets to the four orthogonal neighbours: up, right, down, left.
+pub const NEIGHBOURS_4: [(isize, isize); 4] = [(0, -1), (1, 0), (0, 1), (-1, 0)];
+
+/// Offsets to all eight neighbours, clockwise starting from up.
+pub const NEIGHBOURS_8: [(isize, isize); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// A rectangular grid of typed cells, stored row by row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    cells: Vec<T>,
+    rows: usize,
+    columns: usize,
+}
+
+impl Grid<char> {
+    /// Builds a grid of characters from the raw puzzle input.
+    pub fn build(raw_data: &str) -> Result<Grid<char>, PuzzleError> {
+        Grid::parse(raw_data, Some)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Builds a grid by converting every character with `cell`.
+    ///
+    /// Both `\n` and `\r\n` line endings are accepted and blank lines
+    /// before and after the grid are ignored. Rows must all have the
+    /// same length.
+    pub fn parse<F>(raw_data: &str, mut cell: F) -> Result<Grid<T>, PuzzleError>
+    where
+        F: FnMut(char) -> Option<T>,
+    {
+        Grid::parse_with(raw_data, str::chars, |c| {
+            cell(c).ok_or_else(|| format!("unexpected cell '{c}'"))
+        })
+    }
+
+    /// Builds a grid whose cells are those `split` cuts every line into,
+    /// such as its grapheme clusters, with the same rules as
+    /// [`Grid::parse`].
+    pub fn parse_cells<'a, S, I>(raw_data: &'a str, split: S) -> Result<Grid<T>, PuzzleError>
+    where
+        S: FnMut(&'a str) -> I,
+        I: Iterator<Item = T>,
+    {
+        Grid::parse_with(raw_data, split, Ok)
+    }
+
+    // Cuts every line with `split`, then converts the pieces with `cell`,
+    // which describes what is wrong with those it rejects
+    fn parse_with<'a, S, I, F>(
+        raw_data: &'a str,
+        mut split: S,
+        mut cell: F,
+    ) -> Result<Grid<T>, PuzzleError>
+    where
+        S: FnMut(&'a str) -> I,
+        I: Iterator,
+        F: FnMut(I::Item) -> Result<T, String>,
+    {
+        let lines: Vec<&str> = raw_data.lines().collect();
+        let first = lines.iter().position(|l| !l.trim().is_empty());
+        let last = lines.iter().rposition(|l| !l.trim().is_empty());
+
+        let (first, last) = match (first, last) {
+            (Some(first), Some(last)) => (first, last),
+            _ => return Err(PuzzleError::invalid("no rows to parse")),
+        };
+
+        let columns = split(lines[first]).count();
+        let mut cells = Vec::with_capacity(columns * (last - first + 1));
+        for (index, line) in lines.iter().enumerate().take(last + 1).skip(first) {
+            let length = split(line).count();
+            if length != columns {
+                return Err(PuzzleError::at(
+                    index + 1,
+                    length.min(columns) + 1,
+                    format!("expected {columns} cells per row, found {length}"),
+                ));
+            }
+
+            for (column, piece) in split(line).enumerate() {
+                let value = cell(piece)
+                    .map_err(|description| PuzzleError::at(index + 1, column + 1, description))?;
+                cells.push(value);
+            }
+        }
+
+        Ok(Grid {
+            cells,
+            rows: last - first + 1,
+            columns,
+        })
+    }
+
+    /// Builds a grid with every cell set to `value`.
+    pub fn filled(columns: usize, rows: usize, value: T) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            cells: vec![value; columns * rows],
+            rows,
+            columns,
+        }
+    }
+
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    pub fn contains(&self, position: Position) -> bool {
+        position.0 < self.columns && position.1 < self.rows
+    }
+
+    /// Converts a signed position to a grid position, if it lies inside
+    /// the grid.
+    pub fn checked_position(&self, position: (i64, i64)) -> Option<Position> {
+        let x = usize::try_from(position.0).ok()?;
+        let y = usize::try_from(position.1).ok()?;
+        if self.contains((x, y)) {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    /// Moves `position` by `delta`, if the result lies inside the grid.
+    pub fn offset(&self, position: Position, delta: (isize, isize)) -> Option<Position> {
+        let x = position.0.checked_add_signed(delta.0)?;
+        let y = position.1.checked_add_signed(delta.1)?;
+        if self.contains((x, y)) {
+            Some((x, y))
+        } else {
+            None
+        }
+    }
+
+    pub fn get(&self, position: Position) -> Option<&T> {
+        if self.contains(position) {
+            Some(&self.cells[self.index_of(position)])
+        } else {
+            None
+        }
+    }
+
+    pub fn get_mut(&mut self, position: Position) -> Option<&mut T> {
+        if self.contains(position) {
+            let index = self.index_of(position);
+            Some(&mut self.cells[index])
+        } else {
+            None
+        }
+    }
+
+    /// Replaces the cell at `position`, returning the previous value, or
+    /// `None` if the position lies outside the grid.
+    pub fn set(&mut self, position: Position, value: T) -> Option<T> {
+        let cell = self.get_mut(position)?;
+        Some(std::mem::replace(cell, value))
+    }
+
+    /// Orthogonal neighbours of `position` that lie inside the grid.
+    pub fn neighbours_4(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        NEIGHBOURS_4
+            .iter()
+            .filter_map(move |delta| self.offset(position, *delta))
+    }
+
+    /// Orthogonal and diagonal neighbours of `position` that lie inside
+    /// the grid.
+    pub fn neighbours_8(&self, position: Position) -> impl Iterator<Item = Position> + '_ {
+        NEIGHBOURS_8
+            .iter()
+            .filter_map(move |delta| self.offset(position, *delta))
+    }
+
+    /// All positions, row by row.
+    pub fn positions(&self) -> impl Iterator<Item = Position> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |y| (0..columns).map(move |x| (x, y)))
+    }
+
+    /// All cells along with their position, row by row.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> &[T] {
+        assert!(y < self.rows, "y = {y}");
+        &self.cells[y * self.columns..(y + 1) * self.columns]
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        assert!(x < self.columns, "x = {x}");
+        self.cells.iter().skip(x).step_by(self.columns)
+    }
+
+    pub fn iter_rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.columns.max(1))
+    }
+
+    pub fn iter_columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.columns).map(move |x| self.column(x))
+    }
+
+    /// First position, row by row, holding `value`.
+    pub fn find(&self, value: &T) -> Option<Position>
+    where
+        T: PartialEq,
+    {
+        self.iter().find(|(_, c)| *c == value).map(|(p, _)| p)
+    }
+
+    /// Every position holding `value`, row by row.
+    pub fn find_all<'a>(&'a self, value: &'a T) -> impl Iterator<Item = Position> + 'a
+    where
+        T: PartialEq,
+    {
+        self.iter()
+            .filter(move |(_, c)| *c == value)
+            .map(|(p, _)| p)
+    }
+
+    fn index_of(&self, position: Position) -> usize {
+        position.1 * self.columns + position.0
+    }
+}
+
+impl<T> Index<Position> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, position: Position) -> &T {
+        assert!(position.0 < self.columns, "x = {}", position.0);
+        assert!(position.1 < self.rows, "y = {}", position.1);
+
+        &self.cells[self.index_of(position)]
+    }
+}
+
+impl<T> IndexMut<Position> for Grid<T> {
+    fn index_mut(&mut self, position: Position) -> &mut T {
+        assert!(position.0 < self.columns, "x = {}", position.0);
+        assert!(position.1 < self.rows, "y = {}", position.1);
+
+        let index = self.index_of(position);
+        &mut self.cells[index]
+    }
+}
+
+/// Renders one row per line, without a trailing newline.
+impl<T: fmt::Display> fmt::Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.iter_rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn build_sample_grid() {
+        let data = "\
+#..
+.@.
+..#";
+
+        let grid = Grid::build(data).unwrap();
+        assert_eq!(grid.rows(), 3);
+        assert_eq!(grid.columns(), 3);
+        assert_eq!(grid[(1, 1)], '@');
+        assert_eq!(grid.find(&'@'), Some((1, 1)));
+        assert_eq!(
+            grid.find_all(&'#').collect::<Vec<_>>(),
+            vec![(0, 0), (2, 2)]
+        );
+        assert_eq!(grid.to_string(), data);
+    }
+
+    #[test]
+    fn build_accepts_crlf_and_blank_lines() {
+        let data = "\r\n\nab\r\ncd\r\n\r\n\n";
+
+        let grid = Grid::build(data).unwrap();
+        assert_eq!(grid.rows(), 2);
+        assert_eq!(grid.columns(), 2);
+        assert_eq!(grid.to_string(), "ab\ncd");
+    }
+
+    #[test]
+    fn build_rejects_ragged_and_empty_input() {
+        let error = Grid::build("abc\nab").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 3: expected 3 cells per row, found 2"
+        );
+
+        let error = Grid::build("\nab\n\nab").unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 3, column 1: expected 2 cells per row, found 0"
+        );
+
+        let error = Grid::build("\n \n").unwrap_err();
+        assert_eq!(error.to_string(), "no rows to parse");
+    }
+
+    #[test]
+    fn parse_typed_cells() {
+        let grid = Grid::parse("012\n345", |c| c.to_digit(10)).unwrap();
+        assert_eq!(grid[(2, 1)], 5);
+        assert_eq!(grid.row(0), &[0, 1, 2]);
+        assert_eq!(grid.column(1).copied().collect::<Vec<_>>(), vec![1, 4]);
+        let error = Grid::parse("012\n01x", |c| c.to_digit(10)).unwrap_err();
+        assert_eq!(error.to_string(), "line 2, column 3: unexpected cell 'x'");
+    }
+
+    #[test]
+    fn parse_split_cells() {
+        let grid = Grid::parse_cells("\n10 2 30\n4 50 6\n\n", |line| line.split(' ')).unwrap();
+        assert_eq!((grid.columns(), grid.rows()), (3, 2));
+        assert_eq!(grid[(1, 1)], "50");
+
+        let error = Grid::parse_cells("1 2\n3", |line| line.split(' ')).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "line 2, column 2: expected 2 cells per row, found 1"
+        );
+    }
+
+    #[test]
+    fn bounds_checked_access() {
+        let mut grid = Grid::filled(3, 2, '.');
+        assert_eq!(grid.get((3, 0)), None);
+        assert_eq!(grid.set((2, 1), '#'), Some('.'));
+        assert_eq!(grid.set((0, 2), '#'), None);
+        assert_eq!(grid.get((2, 1)), Some(&'#'));
+        assert_eq!(grid.checked_position((-1, 0)), None);
+        assert_eq!(grid.checked_position((2, 1)), Some((2, 1)));
+        assert_eq!(grid.offset((0, 0), (-1, 0)), None);
+        assert_eq!(grid.offset((0, 0), (1, 1)), Some((1, 1)));
+    }
+
+    #[test]
+    fn neighbours_stay_inside_grid() {
+        let grid = Grid::filled(3, 3, 0);
+        assert_eq!(grid.neighbours_4((0, 0)).count(), 2);
+        assert_eq!(grid.neighbours_4((1, 1)).count(), 4);
+        assert_eq!(grid.neighbours_8((0, 0)).count(), 3);
+        assert_eq!(grid.neighbours_8((1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbours_4((1, 0)).collect::<Vec<_>>(),
+            vec![(2, 0), (1, 1), (0, 0)]
+        );
+    }
+
+    #[test]
+    fn iterate_rows_and_columns() {
+        let grid = Grid::build("ab\ncd\nef").unwrap();
+        let rows: Vec<String> = grid.iter_rows().map(|r| r.iter().collect()).collect();
+        assert_eq!(rows, vec!["ab", "cd", "ef"]);
+
+        let columns: Vec<String> = grid.iter_columns().map(|c| c.collect()).collect();
+        assert_eq!(columns, vec!["ace", "bdf"]);
+    }
+}
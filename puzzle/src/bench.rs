@@ -0,0 +1,21 @@
+use crate::Solution;
+use criterion::{black_box, Criterion};
+use std::fs;
+
+/// Reads a benchmark input, panicking with the file name if it is missing.
+pub fn read_input(file: &str) -> String {
+    fs::read_to_string(file).unwrap_or_else(|e| panic!("{file}: {e}"))
+}
+
+/// Benchmarks parsing and each part of `S` separately, as
+/// `<name>/parse`, `<name>/part1` and `<name>/part2`, so that reports of
+/// different runs and baselines can be compared phase by phase.
+pub fn phases<S: Solution>(c: &mut Criterion, name: &str, raw_input: &str) {
+    let input = S::parse(raw_input).unwrap_or_else(|e| panic!("{name}: {e}"));
+
+    let mut group = c.benchmark_group(name);
+    group.bench_function("parse", |b| b.iter(|| S::parse(black_box(raw_input))));
+    group.bench_function("part1", |b| b.iter(|| S::part1(black_box(&input))));
+    group.bench_function("part2", |b| b.iter(|| S::part2(black_box(&input))));
+    group.finish();
+}
@@ -0,0 +1,294 @@
+use std::{error, fmt, fs, io};
+
+#[cfg(feature = "bench")]
+pub mod bench;
+pub mod output;
+
+/// A one-based position inside a puzzle input.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Location {
+    pub line: usize,
+    pub column: usize,
+}
+
+/// Error shared by every day: bad arguments, unreadable files or
+/// malformed puzzle input.
+#[derive(Debug)]
+pub enum PuzzleError {
+    /// Invalid command line arguments.
+    Usage(String),
+    /// A puzzle input file could not be read.
+    Io { file: String, source: io::Error },
+    /// A puzzle input was read but could not be understood.
+    Input {
+        file: Option<String>,
+        location: Option<Location>,
+        description: String,
+    },
+}
+
+impl PuzzleError {
+    pub fn usage(description: impl Into<String>) -> PuzzleError {
+        PuzzleError::Usage(description.into())
+    }
+
+    /// Malformed input at a known line and column.
+    pub fn at(line: usize, column: usize, description: impl Into<String>) -> PuzzleError {
+        PuzzleError::Input {
+            file: None,
+            location: Some(Location { line, column }),
+            description: description.into(),
+        }
+    }
+
+    /// Malformed input that cannot be pinned to a single position.
+    pub fn invalid(description: impl Into<String>) -> PuzzleError {
+        PuzzleError::Input {
+            file: None,
+            location: None,
+            description: description.into(),
+        }
+    }
+
+    /// Converts a CSV error, using the record's line and the field number
+    /// as column.
+    pub fn from_csv(error: csv::Error) -> PuzzleError {
+        let line = error.position().map(|p| p.line() as usize);
+        let column = match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => err.field().map(|f| f as usize + 1),
+            _ => None,
+        };
+
+        let description = match error.kind() {
+            csv::ErrorKind::Deserialize { err, .. } => err.kind().to_string(),
+            _ => error.to_string(),
+        };
+
+        PuzzleError::Input {
+            file: None,
+            location: line.map(|line| Location {
+                line,
+                column: column.unwrap_or(1),
+            }),
+            description,
+        }
+    }
+
+    /// Where in the input the error was found, if known.
+    pub fn location(&self) -> Option<Location> {
+        match self {
+            PuzzleError::Input { location, .. } => *location,
+            _ => None,
+        }
+    }
+
+    /// Attaches the name of the file the input was read from.
+    pub fn in_file(self, file: &str) -> PuzzleError {
+        match self {
+            PuzzleError::Input {
+                location,
+                description,
+                ..
+            } => PuzzleError::Input {
+                file: Some(file.to_string()),
+                location,
+                description,
+            },
+            other => other,
+        }
+    }
+
+    /// Moves an error found while parsing a single line to `line`.
+    pub fn on_line(self, line: usize) -> PuzzleError {
+        match self {
+            PuzzleError::Input {
+                file,
+                location,
+                description,
+            } => PuzzleError::Input {
+                file,
+                location: Some(Location {
+                    line,
+                    column: location.map_or(1, |l| l.column),
+                }),
+                description,
+            },
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for PuzzleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PuzzleError::Usage(description) => write!(f, "{description}"),
+            PuzzleError::Io { file, source } => write!(f, "{file}: {source}"),
+            PuzzleError::Input {
+                file,
+                location,
+                description,
+            } => match (file, location) {
+                (Some(file), Some(l)) => {
+                    write!(f, "{file}:{}:{}: {description}", l.line, l.column)
+                }
+                (Some(file), None) => write!(f, "{file}: {description}"),
+                (None, Some(l)) => {
+                    write!(f, "line {}, column {}: {description}", l.line, l.column)
+                }
+                (None, None) => write!(f, "{description}"),
+            },
+        }
+    }
+}
+
+impl error::Error for PuzzleError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            PuzzleError::Io { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
+
+/// A day of the puzzle: how to parse its input and how to solve both parts
+/// from the parsed input.
+pub trait Solution {
+    /// The puzzle input once parsed.
+    type Input;
+    type Answer: fmt::Display;
+
+    fn parse(raw_input: &str) -> Result<Self::Input, PuzzleError>;
+
+    fn part1(input: &Self::Input) -> Self::Answer;
+
+    fn part2(input: &Self::Input) -> Self::Answer;
+
+    /// Parses `raw_input` and solves both parts.
+    fn solve(raw_input: &str) -> Result<(Self::Answer, Self::Answer), PuzzleError> {
+        let input = Self::parse(raw_input)?;
+        Ok((Self::part1(&input), Self::part2(&input)))
+    }
+}
+
+/// Reads a whole puzzle input, keeping the file name in the error.
+pub fn read_input(file: &str) -> Result<String, PuzzleError> {
+    fs::read_to_string(file).map_err(|source| PuzzleError::Io {
+        file: file.to_string(),
+        source,
+    })
+}
+
+/// One-based line and column of a byte offset in `raw_content`.
+pub fn location_of(raw_content: &str, offset: usize) -> Location {
+    let before = &raw_content[..offset.min(raw_content.len())];
+    let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+    Location {
+        line: before.matches('\n').count() + 1,
+        column: before[line_start..].chars().count() + 1,
+    }
+}
+
+/// One-based column of `token`, which must be a slice of `line`.
+pub fn column_of(line: &str, token: &str) -> usize {
+    let offset = token.as_ptr() as usize - line.as_ptr() as usize;
+    assert!(offset <= line.len(), "token is not part of line");
+
+    line[..offset].chars().count() + 1
+}
+
+/// Parses a number found in `line`, reporting its column on failure.
+pub fn parse_number<T: std::str::FromStr>(line: &str, token: &str) -> Result<T, PuzzleError> {
+    token.parse().map_err(|_| {
+        PuzzleError::at(
+            1,
+            column_of(line, token),
+            format!("invalid number '{token}'"),
+        )
+    })
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display_input_error_with_location() {
+        let error = PuzzleError::at(3, 7, "invalid number 'x'");
+        assert_eq!(error.to_string(), "line 3, column 7: invalid number 'x'");
+        assert_eq!(
+            error.in_file("input.txt").to_string(),
+            "input.txt:3:7: invalid number 'x'"
+        );
+    }
+
+    #[test]
+    fn display_input_error_without_location() {
+        let error = PuzzleError::invalid("no robot in map").in_file("map.txt");
+        assert_eq!(error.to_string(), "map.txt: no robot in map");
+    }
+
+    #[test]
+    fn parse_number_reports_column() {
+        let line = "7 6 x4 2";
+        let token = line.split_whitespace().nth(2).unwrap();
+        let error = parse_number::<i32>(line, token).unwrap_err().on_line(5);
+        assert_eq!(error.to_string(), "line 5, column 5: invalid number 'x4'");
+    }
+
+    #[test]
+    fn column_counts_characters() {
+        let line = "é,ü,42";
+        let token = line.split(',').nth(2).unwrap();
+        assert_eq!(column_of(line, token), 5);
+    }
+
+    struct Sum;
+
+    impl Solution for Sum {
+        type Input = Vec<u32>;
+        type Answer = u32;
+
+        fn parse(raw_input: &str) -> Result<Vec<u32>, PuzzleError> {
+            raw_input
+                .split_whitespace()
+                .map(|token| parse_number(raw_input, token))
+                .collect()
+        }
+
+        fn part1(input: &Vec<u32>) -> u32 {
+            input.iter().sum()
+        }
+
+        fn part2(input: &Vec<u32>) -> u32 {
+            input.iter().max().copied().unwrap_or(0)
+        }
+    }
+
+    #[test]
+    fn solve_both_parts() {
+        assert_eq!(Sum::solve("3 9 4").unwrap(), (16, 9));
+        assert!(Sum::solve("3 x 4").is_err());
+    }
+
+    #[test]
+    fn read_missing_input() {
+        let error = read_input("does-not-exist.txt").unwrap_err();
+        assert!(error.to_string().starts_with("does-not-exist.txt: "));
+    }
+
+    #[test]
+    fn csv_error_points_to_record_and_field() {
+        let data = "a,b\n1,2\n3,x\n";
+        let mut rdr = csv::Reader::from_reader(data.as_bytes());
+        let error = rdr
+            .deserialize::<(i32, i32)>()
+            .find_map(|r| r.err())
+            .unwrap();
+        let error = PuzzleError::from_csv(error);
+        assert!(error.to_string().starts_with("line 3, column 2: "));
+    }
+}
@@ -0,0 +1,364 @@
+use crate::PuzzleError;
+use std::cell::Cell;
+use std::fmt::Display;
+use std::process;
+use std::time::Duration;
+
+const CSV_HEADER: [&str; 10] = [
+    "kind",
+    "day",
+    "part",
+    "answer",
+    "verdict",
+    "input",
+    "elapsed_ms",
+    "error",
+    "line",
+    "column",
+];
+
+/// How binaries print their results.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// Free-text lines meant for people.
+    Text,
+    /// One JSON object per line.
+    Json,
+    /// A CSV table with a header row.
+    Csv,
+}
+
+impl Format {
+    fn parse(value: &str) -> Result<Format, PuzzleError> {
+        match value {
+            "text" => Ok(Format::Text),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            _ => Err(PuzzleError::usage(format!(
+                "Invalid format '{value}': must be json, csv or text"
+            ))),
+        }
+    }
+
+    /// Removes `--format <json|csv|text>` (or `--format=...`) from `args`,
+    /// defaulting to text.
+    pub fn take(args: &mut Vec<String>) -> Result<Format, PuzzleError> {
+        let mut format = Format::Text;
+        let mut index = 0;
+        while index < args.len() {
+            if args[index] == "--format" {
+                if index + 1 >= args.len() {
+                    return Err(PuzzleError::usage("Missing value after --format"));
+                }
+                format = Format::parse(&args[index + 1])?;
+                args.drain(index..index + 2);
+            } else if let Some(value) = args[index].strip_prefix("--format=") {
+                format = Format::parse(value)?;
+                args.remove(index);
+            } else {
+                index += 1;
+            }
+        }
+
+        Ok(format)
+    }
+}
+
+/// A single structured result: either an answer or an error.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Record {
+    Answer {
+        day: u32,
+        part: u8,
+        // `None` for parts a day does not solve
+        answer: Option<String>,
+        verdict: Option<String>,
+        input: String,
+        elapsed: Duration,
+    },
+    Error {
+        day: Option<u32>,
+        input: Option<String>,
+        error: String,
+        line: Option<usize>,
+        column: Option<usize>,
+    },
+}
+
+impl Record {
+    pub fn answer(
+        day: u32,
+        part: u8,
+        answer: impl Display,
+        input: &str,
+        elapsed: Duration,
+    ) -> Record {
+        Record::Answer {
+            day,
+            part,
+            answer: Some(answer.to_string()),
+            verdict: None,
+            input: input.to_string(),
+            elapsed,
+        }
+    }
+
+    pub fn error(day: Option<u32>, input: Option<&str>, error: &dyn Display) -> Record {
+        Record::Error {
+            day,
+            input: input.map(str::to_string),
+            error: error.to_string(),
+            line: None,
+            column: None,
+        }
+    }
+
+    /// An error record carrying the location of a malformed input.
+    pub fn puzzle_error(day: Option<u32>, input: Option<&str>, error: &PuzzleError) -> Record {
+        let location = error.location();
+        Record::Error {
+            day,
+            input: input.map(str::to_string),
+            error: error.to_string(),
+            line: location.map(|l| l.line),
+            column: location.map(|l| l.column),
+        }
+    }
+
+    // every field, in the order of CSV_HEADER
+    fn fields(&self) -> [Option<String>; 10] {
+        match self {
+            Record::Answer {
+                day,
+                part,
+                answer,
+                verdict,
+                input,
+                elapsed,
+            } => [
+                Some(String::from("answer")),
+                Some(day.to_string()),
+                Some(part.to_string()),
+                answer.clone(),
+                verdict.clone(),
+                Some(input.clone()),
+                Some(elapsed_ms(*elapsed).to_string()),
+                None,
+                None,
+                None,
+            ],
+            Record::Error {
+                day,
+                input,
+                error,
+                line,
+                column,
+            } => [
+                Some(String::from("error")),
+                day.map(|d| d.to_string()),
+                None,
+                None,
+                None,
+                input.clone(),
+                None,
+                Some(error.clone()),
+                line.map(|l| l.to_string()),
+                column.map(|c| c.to_string()),
+            ],
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let mut object = serde_json::Map::new();
+        let mut insert = |key: &str, value: serde_json::Value| {
+            if !value.is_null() {
+                object.insert(key.to_string(), value);
+            }
+        };
+
+        match self {
+            Record::Answer {
+                day,
+                part,
+                answer,
+                verdict,
+                input,
+                elapsed,
+            } => {
+                insert("kind", "answer".into());
+                insert("day", (*day).into());
+                insert("part", (*part).into());
+                insert("answer", answer.clone().into());
+                insert("verdict", verdict.clone().into());
+                insert("input", input.clone().into());
+                insert("elapsed_ms", elapsed_ms(*elapsed).into());
+            }
+            Record::Error {
+                day,
+                input,
+                error,
+                line,
+                column,
+            } => {
+                insert("kind", "error".into());
+                insert("day", (*day).into());
+                insert("input", input.clone().into());
+                insert("error", error.clone().into());
+                insert("line", (*line).into());
+                insert("column", (*column).into());
+            }
+        }
+
+        serde_json::Value::Object(object).to_string()
+    }
+}
+
+// milliseconds, down to the microsecond
+fn elapsed_ms(elapsed: Duration) -> f64 {
+    elapsed.as_micros() as f64 / 1000.0
+}
+
+/// Prints records in the format picked on the command line.
+#[derive(Debug)]
+pub struct Output {
+    format: Format,
+    header_written: Cell<bool>,
+}
+
+impl Output {
+    pub fn new(format: Format) -> Output {
+        Output {
+            format,
+            header_written: Cell::new(false),
+        }
+    }
+
+    /// Builds the output from `--format`, removing the option from `args`.
+    /// An invalid format is reported as text and ends the process.
+    pub fn from_args(args: &mut Vec<String>) -> Output {
+        let format = Format::take(args).unwrap_or_else(|err| {
+            eprintln!("Problem parsing arguments: {err}");
+            process::exit(1);
+        });
+
+        Output::new(format)
+    }
+
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    pub fn is_text(&self) -> bool {
+        self.format == Format::Text
+    }
+
+    /// Prints a record; text output is left to the callers.
+    pub fn emit(&self, record: &Record) {
+        match self.format {
+            Format::Text => (),
+            Format::Json => println!("{}", record.to_json()),
+            Format::Csv => {
+                let mut writer = csv::Writer::from_writer(Vec::new());
+                if !self.header_written.replace(true) {
+                    writer.write_record(CSV_HEADER).expect("writing to memory");
+                }
+                let fields = record.fields().map(Option::unwrap_or_default);
+                writer.write_record(&fields).expect("writing to memory");
+                let bytes = writer.into_inner().expect("writing to memory");
+                print!("{}", String::from_utf8_lossy(&bytes));
+            }
+        }
+    }
+
+    /// Prints the answers of `day`, part 1 first.
+    pub fn answers(&self, day: u32, input: &str, answers: &[&dyn Display], elapsed: Duration) {
+        for (index, answer) in answers.iter().enumerate() {
+            self.emit(&Record::answer(
+                day,
+                index as u8 + 1,
+                answer,
+                input,
+                elapsed,
+            ));
+        }
+    }
+
+    /// Reports `err` and ends the process: as `context: err` on stderr in
+    /// text mode, as an error record otherwise.
+    pub fn fail(&self, day: u32, input: Option<&str>, context: &str, err: &PuzzleError) -> ! {
+        if self.is_text() {
+            eprintln!("{context}: {err}");
+        } else {
+            self.emit(&Record::puzzle_error(Some(day), input, err));
+        }
+        process::exit(1);
+    }
+}
+
+// Note on printing during tests:
+// - Run test sequentially in case of need with: cargo test -- --test-threads 1
+// - Do not capture test output for debug with: cargo test -- --nocapture
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn take_format_from_args() {
+        let mut a = args(&["day1", "--format", "json", "input.txt"]);
+        assert_eq!(Format::take(&mut a).unwrap(), Format::Json);
+        assert_eq!(a, args(&["day1", "input.txt"]));
+
+        let mut a = args(&["day1", "input.txt", "--format=csv"]);
+        assert_eq!(Format::take(&mut a).unwrap(), Format::Csv);
+        assert_eq!(a, args(&["day1", "input.txt"]));
+
+        let mut a = args(&["day1", "input.txt"]);
+        assert_eq!(Format::take(&mut a).unwrap(), Format::Text);
+
+        assert!(Format::take(&mut args(&["day1", "--format", "xml"])).is_err());
+        assert!(Format::take(&mut args(&["day1", "--format"])).is_err());
+    }
+
+    #[test]
+    fn answer_record_as_json() {
+        let record = Record::answer(1, 2, 31, "in.txt", Duration::from_micros(1500));
+        assert_eq!(
+            record.to_json(),
+            r#"{"kind":"answer","day":1,"part":2,"answer":"31","input":"in.txt","elapsed_ms":1.5}"#
+        );
+
+        let error = PuzzleError::usage("Not enough arguments");
+        let record = Record::puzzle_error(Some(1), None, &error);
+        assert_eq!(
+            record.to_json(),
+            r#"{"kind":"error","day":1,"error":"Not enough arguments"}"#
+        );
+    }
+
+    #[test]
+    fn error_record_fields() {
+        let error = PuzzleError::at(3, 7, "invalid number 'x'").in_file("in.txt");
+        let record = Record::puzzle_error(Some(2), Some("in.txt"), &error);
+        let fields = record.fields().map(Option::unwrap_or_default);
+        assert_eq!(
+            fields,
+            [
+                "error",
+                "2",
+                "",
+                "",
+                "",
+                "in.txt",
+                "",
+                "in.txt:3:7: invalid number 'x'",
+                "3",
+                "7"
+            ]
+        );
+    }
+}
@@ -1,55 +1,201 @@
-use csv::Reader;
-use serde::de::DeserializeOwned;
-use std::io::{self, Read};
-use std::{error::Error, fs::File, process};
-
-#[derive(Debug, serde::Deserialize)]
-struct Entry {
-    output_start: i32,
-    input_start: i32,
-    input_range: i32,
+use puzzle::PuzzleError;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const USAGE: &str = "Usage: new-day <day> [--root <workspace>]";
+
+// Files of a new day, relative to its crate directory
+const FILES: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("../day/Cargo.toml.tmpl")),
+    ("src/lib.rs", include_str!("../day/src/lib.rs.tmpl")),
+    ("src/main.rs", include_str!("../day/src/main.rs.tmpl")),
+    (
+        "content/sample-input.txt",
+        include_str!("../day/content/sample-input.txt"),
+    ),
+];
+
+/// A list of days, kept in increasing order, inside a workspace file.
+struct Listing {
+    // text starting the line of the entry of a day
+    entry: fn(u32) -> String,
+    // text starting the line days past the last listed one go before
+    end: &'static str,
+    template: &'static str,
+}
+
+impl Listing {
+    fn insert(&self, content: &str, day: u32) -> Result<String, PuzzleError> {
+        let find = |text: &str| content.find(&format!("\n{text}")).map(|index| index + 1);
+
+        if find(&(self.entry)(day)).is_some() {
+            return Err(PuzzleError::invalid(format!(
+                "day{day} is already registered"
+            )));
+        }
+
+        let index = (day + 1..=25)
+            .find_map(|next| find(&(self.entry)(next)))
+            .or_else(|| find(self.end))
+            .ok_or_else(|| {
+                PuzzleError::invalid(format!("cannot find where to register day{day}"))
+            })?;
+
+        let mut content = content.to_string();
+        content.insert_str(index, &render(self.template, day));
+        Ok(content)
+    }
+}
+
+// Workspace files a new day is registered in, relative to the root
+const REGISTRATIONS: &[(&str, &[Listing])] = &[
+    (
+        "Cargo.toml",
+        &[Listing {
+            entry: |day| format!("    \"day{day}\","),
+            end: "    \"fetch\",",
+            template: "    \"day{{day}}\",\n",
+        }],
+    ),
+    (
+        "aoc/Cargo.toml",
+        &[Listing {
+            entry: |day| format!("day{day} = "),
+            end: "fetch = ",
+            template: "day{{day}} = { path = \"../day{{day}}\" }\n",
+        }],
+    ),
+    (
+        "aoc/src/lib.rs",
+        &[
+            Listing {
+                entry: |day| format!("    Day {{\n        number: {day},"),
+                end: "];\n\nfn find_day",
+                template: "    Day {
+        number: {{day}},
+        inputs: &[\"day{{day}}/content/puzzle-input.txt\"],
+        extra_args: &[],
+        solve: solve_day{{day}},
+    },
+",
+            },
+            Listing {
+                entry: |day| format!("fn solve_day{day}("),
+                end: "fn bundled_inputs(",
+                template: "fn solve_day{{day}}(args: &[String]) -> Result<Answers, PuzzleError> {
+    let config = day{{day}}::Config::build(args)?;
+    let (part1, part2) = day{{day}}::run(config)?;
+    Ok(Answers::both(part1, part2))
+}
+
+",
+            },
+        ],
+    ),
+];
+
+fn render(template: &str, day: u32) -> String {
+    template.replace("{{day}}", &day.to_string())
 }
 
 #[derive(Debug)]
 pub struct Config {
-    first_file: String,
-    second_file: String,
+    day: u32,
+    root: PathBuf,
 }
 
 impl Config {
-    pub fn build(args: &[String]) -> Result<Config, &'static str> {
-        if args.len() < 3 {
-            return Err("Not enough arguments");
+    pub fn build(args: &[String]) -> Result<Config, PuzzleError> {
+        if args.len() < 2 {
+            return Err(PuzzleError::usage(USAGE));
         }
 
-        let first_file = args[1].clone();
-        let second_file = args[2].clone();
+        let day = match args[1].parse::<u32>() {
+            Ok(day) if (1..=25).contains(&day) => day,
+            _ => {
+                return Err(PuzzleError::usage(
+                    "Invalid day: must be a number from 1 to 25",
+                ))
+            }
+        };
+
+        let root = match &args[2..] {
+            [] => Path::new(env!("CARGO_MANIFEST_DIR"))
+                .parent()
+                .expect("template crate lives inside the workspace")
+                .to_path_buf(),
+            [option, path] if option == "--root" => PathBuf::from(path),
+            _ => return Err(PuzzleError::usage(USAGE)),
+        };
 
-        Ok(Config {
-            first_file,
-            second_file,
-        })
+        Ok(Config { day, root })
     }
 }
 
-fn deserialize<T, R>(reader: R) -> Result<Vec<T>, Box<dyn std::error::Error>>
-where
-    T: std::fmt::Debug + DeserializeOwned,
-    R: Read,
-{
-    let mut rdr = Reader::from_reader(reader);
-    let mut structs: Vec<T> = Vec::new();
-    for result in rdr.deserialize() {
-        let record: T = result?;
-        structs.push(record);
-    }
+/// Files written while scaffolding a day.
+#[derive(Debug)]
+pub struct Scaffold {
+    pub created: Vec<PathBuf>,
+    pub updated: Vec<PathBuf>,
+}
+
+fn write(file: &Path, content: &str) -> Result<(), PuzzleError> {
+    let io_error = |source| PuzzleError::Io {
+        file: file.display().to_string(),
+        source,
+    };
 
-    Ok(structs)
+    if let Some(dir) = file.parent() {
+        fs::create_dir_all(dir).map_err(io_error)?;
+    }
+    fs::write(file, content).map_err(io_error)
 }
 
-pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
-    // TODO
-    Ok(())
+/// Creates the crate of a day from the template and registers it in the
+/// workspace and in the runner. Nothing is written when the day already
+/// exists or cannot be registered.
+pub fn run(config: Config) -> Result<Scaffold, PuzzleError> {
+    let dir = config.root.join(format!("day{}", config.day));
+    if dir.exists() {
+        return Err(PuzzleError::usage(format!(
+            "{} already exists",
+            dir.display()
+        )));
+    }
+
+    let mut registrations = Vec::new();
+    for (file, listings) in REGISTRATIONS {
+        let file = config.root.join(file);
+        let name = file.display().to_string();
+        let mut content = puzzle::read_input(&name)?;
+        for listing in listings.iter() {
+            content = listing
+                .insert(&content, config.day)
+                .map_err(|e| e.in_file(&name))?;
+        }
+        registrations.push((file, content));
+    }
+
+    // create_dir() fails if the day appeared in the meantime
+    fs::create_dir(&dir).map_err(|source| PuzzleError::Io {
+        file: dir.display().to_string(),
+        source,
+    })?;
+
+    let mut created = Vec::new();
+    for (file, template) in FILES {
+        let file = dir.join(file);
+        write(&file, &render(template, config.day))?;
+        created.push(file);
+    }
+
+    let mut updated = Vec::new();
+    for (file, content) in registrations {
+        write(&file, &content)?;
+        updated.push(file);
+    }
+
+    Ok(Scaffold { created, updated })
 }
 
 // Note on printing during tests:
@@ -58,29 +204,99 @@ pub fn run(config: Config) -> Result<(), Box<dyn Error>> {
 
 #[cfg(test)]
 mod tests {
-    use io::BufReader;
-
     use super::*;
 
+    fn args(raw: &[&str]) -> Vec<String> {
+        raw.iter().map(|s| s.to_string()).collect()
+    }
+
+    // A workspace holding copies of the files a day is registered in
+    fn workspace() -> tempfile::TempDir {
+        let real_root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+        let root = tempfile::tempdir().unwrap();
+        for (file, _) in REGISTRATIONS {
+            let content = fs::read_to_string(real_root.join(file)).unwrap();
+            write(&root.path().join(file), &content).unwrap();
+        }
+        root
+    }
+
+    fn read(root: &Path, file: &str) -> String {
+        fs::read_to_string(root.join(file)).unwrap()
+    }
+
+    #[test]
+    fn build_config() {
+        let config = Config::build(&args(&["new-day", "3", "--root", "/tmp/ws"])).unwrap();
+        assert_eq!(config.day, 3);
+        assert_eq!(config.root, PathBuf::from("/tmp/ws"));
+
+        assert!(Config::build(&args(&["new-day"])).is_err());
+        assert!(Config::build(&args(&["new-day", "26"])).is_err());
+        assert!(Config::build(&args(&["new-day", "3", "--root"])).is_err());
+    }
+
     #[test]
-    fn proper_deserialize_from_slice_to_entry() {
-        // Note: must be without spaces
-        let data = "\
-output_start,input_start,input_range
-50,98,2
-52,50,48
-";
-
-        let structs: Vec<Entry> = deserialize(data.as_bytes()).unwrap();
+    fn scaffold_day_in_order() {
+        let root = workspace();
+        let config = Config::build(&args(&[
+            "new-day",
+            "3",
+            "--root",
+            &root.path().display().to_string(),
+        ]))
+        .unwrap();
+
+        let scaffold = run(config).unwrap();
+        assert_eq!(scaffold.created.len(), FILES.len());
+        assert_eq!(scaffold.updated.len(), REGISTRATIONS.len());
+
+        let lib = read(root.path(), "day3/src/lib.rs");
+        assert!(lib.contains("pub struct Day3;"));
+        assert!(!lib.contains("{{day}}"));
+        assert!(root.path().join("day3/content/sample-input.txt").is_file());
+
+        let members = read(root.path(), "Cargo.toml");
+        assert!(members.contains("    \"day2\",\n    \"day3\",\n    \"day4\",\n"));
+
+        let dependencies = read(root.path(), "aoc/Cargo.toml");
+        assert!(dependencies.contains("day3 = { path = \"../day3\" }\nday4 = "));
+
+        let runner = read(root.path(), "aoc/src/lib.rs");
+        assert!(runner.contains("        number: 3,\n"));
+        assert!(runner.contains("fn solve_day3(args: &[String])"));
+        assert!(runner.find("number: 3,") < runner.find("number: 4,"));
+        assert!(runner.find("fn solve_day3(") < runner.find("fn solve_day4("));
+    }
+
+    #[test]
+    fn scaffold_day_past_the_last_one() {
+        let root = workspace();
+        let root_arg = root.path().display().to_string();
+        run(Config::build(&args(&["new-day", "25", "--root", &root_arg])).unwrap()).unwrap();
+
+        let members = read(root.path(), "Cargo.toml");
+        assert!(members.contains("    \"day25\",\n    \"fetch\",\n"));
+        let runner = read(root.path(), "aoc/src/lib.rs");
+        assert!(runner.contains("fn solve_day25(args: &[String])"));
+        assert!(runner.contains("        solve: solve_day25,\n    },\n];\n"));
     }
 
     #[test]
-    fn proper_deserialize_from_file_to_entry() {
-        // Note: must be without spaces and by default the base directory should be at the same
-        // level of src
-        let file = File::open("content/sample-content.csv").unwrap();
-        let reader = BufReader::new(file);
+    fn existing_day_is_not_overwritten() {
+        let root = workspace();
+        let root_arg = root.path().display().to_string();
+        fs::create_dir(root.path().join("day1")).unwrap();
+
+        let before = read(root.path(), "aoc/src/lib.rs");
+        let config = Config::build(&args(&["new-day", "1", "--root", &root_arg])).unwrap();
+        assert!(run(config).is_err());
+        assert_eq!(read(root.path(), "aoc/src/lib.rs"), before);
 
-        let structs: Vec<Entry> = deserialize(reader).unwrap();
+        // registered without a crate directory
+        let config = Config::build(&args(&["new-day", "2", "--root", &root_arg])).unwrap();
+        let error = run(config).unwrap_err();
+        assert!(error.to_string().ends_with("day2 is already registered"));
+        assert!(!root.path().join("day2").exists());
     }
 }
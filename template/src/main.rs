@@ -1,3 +1,24 @@
+use std::{env, process};
+
 fn main() {
-    println!("Hello, world!");
+    let args: Vec<String> = env::args().collect();
+    let config = template::Config::build(&args).unwrap_or_else(|err| {
+        eprintln!("Problem parsing arguments: {err}");
+        process::exit(1);
+    });
+
+    match template::run(config) {
+        Ok(scaffold) => {
+            for file in scaffold.created {
+                println!("Created {}", file.display());
+            }
+            for file in scaffold.updated {
+                println!("Updated {}", file.display());
+            }
+        }
+        Err(e) => {
+            eprintln!("Application error: {e}");
+            process::exit(1);
+        }
+    }
 }